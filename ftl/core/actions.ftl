@@ -26,6 +26,7 @@ actions-preview = Preview
 actions-rebuild = Rebuild
 actions-rename = Rename
 actions-rename-deck = Rename Deck
+actions-merge-decks = Merge Decks
 actions-rename-tag = Rename Tag
 actions-rename-with-parents = Rename with Parents
 actions-remove-tag = Remove Tag
@@ -50,6 +50,11 @@ database-check-fixed-invalid-ids =
         [one] Fixed { $count } object with timestamps in the future.
        *[other] Fixed { $count } objects with timestamps in the future.
     }
+database-check-notes-missing-cards =
+    { $count ->
+        [one] Fixed { $count } note with no cards.
+       *[other] Fixed { $count } notes with no cards.
+    }
 # "db-check" is always in English
 database-check-notetypes-recovered = One or more notetypes were missing. The notes that used them have been given new notetypes starting with "db-check", but field names and card design have been lost, so you may be better off restoring from an automatic backup.
 
@@ -17,6 +17,7 @@ exporting-include-deck-configs = Include deck presets
 exporting-include-tags = Include tags
 exporting-support-older-anki-versions = Support older Anki versions (slower/larger files)
 exporting-notes-in-plain-text = Notes in Plain Text
+exporting-notes-in-markdown = Notes in Markdown
 exporting-selected-notes = Selected Notes
 exporting-card-exported =
     { $count ->
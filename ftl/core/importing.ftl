@@ -235,6 +235,16 @@ importing-tag-all-notes-help =
     These tags will be added to both newly-imported and updated notes.
 importing-tag-updated-notes-help = These tags will be added to any updated notes.
 importing-overview = Overview
+importing-verify-media = Verify media files
+importing-verify-media-help =
+    If enabled, each media file will be checked against the size and checksum recorded
+    in the package when it was created. Files that don't match, for example because the
+    download was incomplete, will not be imported. This makes importing large packages slower.
+importing-media-failed-verification =
+    { $count ->
+        [one] { $count } media file was not imported, because it failed verification:
+       *[other] { $count } media files were not imported, because they failed verification:
+    }
 
 ## NO NEED TO TRANSLATE. This text is no longer used by Anki, and will be removed in the future.
 
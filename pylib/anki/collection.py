@@ -400,6 +400,11 @@ class Collection(DeprecatedNamesMixin):
             limit=pb_export_limit(limit),
         )
 
+    def export_note_markdown(self, *, out_path: str, limit: ExportLimit) -> int:
+        return self._backend.export_note_markdown(
+            out_path=out_path, limit=pb_export_limit(limit)
+        )
+
     def export_card_csv(
         self,
         *,
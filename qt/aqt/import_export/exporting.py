@@ -61,6 +61,7 @@ class ExportDialog(QDialog):
             ColpkgExporter,
             NoteCsvExporter,
             CardCsvExporter,
+            NoteMarkdownExporter,
         ]
         gui_hooks.exporters_list_did_initialize(self.exporter_classes)
         self.frm.format.insertItems(
@@ -346,6 +347,31 @@ class CardCsvExporter(Exporter):
         ).with_backend_progress(export_progress_update).run_in_background()
 
 
+class NoteMarkdownExporter(Exporter):
+    extension = "md"
+    show_deck_list = True
+
+    @staticmethod
+    def name() -> str:
+        return tr.exporting_notes_in_markdown()
+
+    def export(self, mw: aqt.main.AnkiQt, options: ExportOptions) -> None:
+        options = gui_hooks.exporter_will_export(options, self)
+
+        def on_success(count: int) -> None:
+            gui_hooks.exporter_did_export(options, self)
+            tooltip(tr.exporting_note_exported(count=count), parent=mw)
+
+        QueryOp(
+            parent=mw,
+            op=lambda col: col.export_note_markdown(
+                out_path=options.out_path,
+                limit=options.limit,
+            ),
+            success=on_success,
+        ).with_backend_progress(export_progress_update).run_in_background()
+
+
 def export_progress_update(progress: Progress, update: ProgressUpdate) -> None:
     if not progress.HasField("exporting"):
         return
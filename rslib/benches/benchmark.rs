@@ -2,6 +2,7 @@
 // License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
 
 use anki::card_rendering::anki_directive_benchmark;
+use anki::collection::CollectionBuilder;
 use criterion::criterion_group;
 use criterion::criterion_main;
 use criterion::Criterion;
@@ -10,5 +11,25 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("anki_tag_parse", |b| b.iter(|| anki_directive_benchmark()));
 }
 
-criterion_group!(benches, criterion_benchmark);
+pub fn deck_rename_benchmark(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut col = CollectionBuilder::new(dir.path().join("bench.anki2"))
+        .build()
+        .unwrap();
+    let deck = col.get_or_create_normal_deck("spanish").unwrap();
+    for i in 0..500 {
+        col.get_or_create_normal_deck(&format!("spanish::{i}"))
+            .unwrap();
+    }
+    let mut upper = false;
+    c.bench_function("deck_case_only_rename", |b| {
+        b.iter(|| {
+            upper = !upper;
+            let name = if upper { "Spanish" } else { "spanish" };
+            col.rename_deck(deck.id, name).unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark, deck_rename_benchmark);
 criterion_main!(benches);
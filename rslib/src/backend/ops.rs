@@ -31,6 +31,8 @@ impl UndoStatus {
             undo: self.undo.map(|op| op.describe(tr)).unwrap_or_default(),
             redo: self.redo.map(|op| op.describe(tr)).unwrap_or_default(),
             last_step: self.last_step as u32,
+            undo_steps: self.undo_steps as u32,
+            redo_steps: self.redo_steps as u32,
         }
     }
 }
@@ -249,7 +249,13 @@ impl Backend {
             (col.media()?, col.new_progress_handler())
         };
         let rt = self.runtime_handle();
-        let sync_fut = mgr.sync_media(progress, auth, self.web_client().clone(), server_usn);
+        let sync_fut = mgr.sync_media(
+            progress,
+            |_| (),
+            auth,
+            self.web_client().clone(),
+            server_usn,
+        );
         let abortable_sync = Abortable::new(sync_fut, abort_reg);
         let result = rt.block_on(abortable_sync);
 
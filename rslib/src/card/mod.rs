@@ -24,6 +24,7 @@ use crate::error::Result;
 use crate::notes::NoteId;
 use crate::ops::StateChanges;
 use crate::prelude::*;
+use crate::search::SortMode;
 use crate::timestamp::TimestampSecs;
 use crate::types::Usn;
 
@@ -373,6 +374,17 @@ impl Collection {
         })
     }
 
+    /// Like [Collection::set_deck], but moves all cards matching the search.
+    /// Cards that are already in the target deck are not counted.
+    pub fn set_deck_for_matching(
+        &mut self,
+        search: &str,
+        target: DeckId,
+    ) -> Result<OpOutput<usize>> {
+        let cids = self.search_cards(search, SortMode::NoOrder)?;
+        self.set_deck(&cids, target)
+    }
+
     pub fn set_card_flag(&mut self, cards: &[CardId], flag: u32) -> Result<OpOutput<usize>> {
         require!(flag < 8, "invalid flag");
         let flag = flag as u8;
@@ -394,6 +406,12 @@ impl Collection {
         })
     }
 
+    /// Remove the flag from the provided cards, leaving any other bits of the
+    /// flags byte untouched. Returns the number of cards that had a flag.
+    pub fn clear_flags(&mut self, cards: &[CardId]) -> Result<OpOutput<usize>> {
+        self.set_card_flag(cards, 0)
+    }
+
     /// Get deck config for the given card. If missing, return default values.
     #[allow(dead_code)]
     pub(crate) fn deck_config_for_card(&mut self, card: &Card) -> Result<DeckConfig> {
@@ -488,9 +506,13 @@ impl From<MemoryState> for FsrsMemoryState {
 
 #[cfg(test)]
 mod test {
+    use crate::error::FilteredDeckError;
+    use crate::prelude::*;
+    use crate::search::SortMode;
     use crate::tests::open_test_collection_with_learning_card;
     use crate::tests::open_test_collection_with_relearning_card;
     use crate::tests::DeckAdder;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn should_increase_remaining_learning_steps_if_new_deck_has_more_unpassed_ones() {
@@ -513,4 +535,54 @@ mod test {
         col.set_deck(&[card_id], deck.id).unwrap();
         assert_eq!(col.get_first_card().remaining_steps, 2);
     }
+
+    #[test]
+    fn moving_cards_by_search() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_notetype();
+        let target = DeckAdder::new("target").add(&mut col);
+        NoteAdder::new(&nt).fields(&["move", ""]).add(&mut col);
+        NoteAdder::new(&nt).fields(&["stay", ""]).add(&mut col);
+        NoteAdder::new(&nt)
+            .fields(&["move", ""])
+            .deck(target.id)
+            .add(&mut col);
+
+        assert_eq!(
+            col.set_deck_for_matching("front:move", target.id)?.output,
+            1
+        );
+        assert_eq!(col.search_cards("deck:target", SortMode::NoOrder)?.len(), 2);
+
+        let filtered = DeckAdder::new("filtered").filtered(true).add(&mut col);
+        assert!(matches!(
+            col.set_deck_for_matching("", filtered.id),
+            Err(AnkiError::FilteredDeckError {
+                source: FilteredDeckError::CanNotMoveCardsInto
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn clearing_flags_preserves_other_bits() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_rev_notetype();
+        let note = NoteAdder::new(&nt).fields(&["a", "b"]).add(&mut col);
+        let cids = col
+            .storage
+            .all_card_ids_of_note_in_template_order(note.id)?;
+        col.set_card_flag(&cids[..1], 3)?;
+        col.storage
+            .db
+            .execute("update cards set flags = flags | 0b1000", [])?;
+
+        assert_eq!(col.clear_flags(&cids)?.output, 1);
+        for cid in cids {
+            assert_eq!(col.storage.get_card(cid)?.unwrap().flags, 0b1000);
+        }
+
+        Ok(())
+    }
 }
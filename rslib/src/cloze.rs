@@ -436,6 +436,42 @@ pub fn add_cloze_numbers_in_string(field: &str, set: &mut HashSet<u16>) {
     add_cloze_numbers_in_text_with_clozes(&parse_text_with_clozes(field), set)
 }
 
+fn add_cloze_numbers_in_order_of_appearance(nodes: &[TextOrCloze], numbers: &mut Vec<u16>) {
+    for node in nodes {
+        if let TextOrCloze::Cloze(cloze) = node {
+            if cloze.ordinal != 0 {
+                if !numbers.contains(&cloze.ordinal) {
+                    numbers.push(cloze.ordinal);
+                }
+                add_cloze_numbers_in_order_of_appearance(&cloze.nodes, numbers);
+            }
+        }
+    }
+}
+
+/// Append cloze numbers in `field` not already in `numbers`, in the order
+/// their opening markers appear. Nested clozes follow their parent.
+pub(crate) fn cloze_numbers_in_order_of_appearance(field: &str, numbers: &mut Vec<u16>) {
+    add_cloze_numbers_in_order_of_appearance(&parse_text_with_clozes(field), numbers)
+}
+
+/// Rewrite the number of every cloze found in `map`. All other text is
+/// left as is.
+pub(crate) fn renumber_clozes_in_string(text: &str, map: &HashMap<u16, u16>) -> String {
+    let mut buf = String::with_capacity(text.len());
+    for token in tokenize(text) {
+        match token {
+            Token::OpenCloze(ordinal) => {
+                let ordinal = map.get(&ordinal).copied().unwrap_or(ordinal);
+                write!(buf, "{{{{c{ordinal}::").unwrap();
+            }
+            Token::Text(text) => buf.push_str(text),
+            Token::CloseCloze => buf.push_str("}}"),
+        }
+    }
+    buf
+}
+
 fn strip_html_inside_mathjax(text: &str) -> Cow<str> {
     MATHJAX.replace_all(text, |caps: &Captures| -> String {
         format!(
@@ -523,6 +559,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn renumbering() {
+        let text = "{{c4::a {{c7::b}}}} {{c2::c::hint}} {{c4::d}} {{c0::e}} }}";
+        let mut numbers = vec![];
+        cloze_numbers_in_order_of_appearance(text, &mut numbers);
+        assert_eq!(numbers, [4, 7, 2]);
+        let map = numbers
+            .iter()
+            .enumerate()
+            .map(|(idx, &ord)| (ord, idx as u16 + 1))
+            .collect();
+        assert_eq!(
+            renumber_clozes_in_string(text, &map),
+            "{{c1::a {{c2::b}}}} {{c3::c::hint}} {{c1::d}} {{c0::e}} }}"
+        );
+    }
+
     #[test]
     fn nested_cloze_plain_text() {
         assert_eq!(
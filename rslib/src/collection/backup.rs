@@ -388,4 +388,41 @@ mod test {
             BackupFilter::new(today, limits).obsolete_backups(backups.into_iter());
         assert_eq!(obsolete_backups, expected);
     }
+
+    #[test]
+    fn thinning_only_removes_parsable_backups() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let now = Local::now();
+        let dated: Vec<_> = [0, 2, 3, 4, 5]
+            .into_iter()
+            .map(|days_ago| {
+                (now - chrono::Duration::days(days_ago))
+                    .format(BACKUP_FORMAT_STRING)
+                    .to_string()
+            })
+            .collect();
+        let unparsable = ["backup-manual.colpkg", "backup-2022-02-30-00.00.00.colpkg"];
+        for name in dated.iter().map(String::as_str).chain(unparsable) {
+            anki_io::write_file(dir.path().join(name), "")?;
+        }
+        let limits = BackupLimits {
+            daily: 2,
+            ..Default::default()
+        };
+
+        thin_backups(dir.path(), limits)?;
+
+        let remaining: Vec<_> = read_dir(dir.path())?
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .sorted()
+            .collect();
+        let expected: Vec<_> = dated[..3]
+            .iter()
+            .map(String::as_str)
+            .chain(unparsable)
+            .sorted()
+            .collect();
+        assert_eq!(remaining, expected);
+        Ok(())
+    }
 }
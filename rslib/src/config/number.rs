@@ -11,12 +11,13 @@ pub enum I32ConfigKey {
     CsvDuplicateResolution,
     MatchScope,
     LastFsrsOptimize,
+    MaxDeckDepth,
 }
 
 impl Collection {
     pub fn get_config_i32(&self, key: I32ConfigKey) -> i32 {
-        #[allow(clippy::match_single_binding)]
         self.get_config_optional(key).unwrap_or(match key {
+            I32ConfigKey::MaxDeckDepth => 10,
             _other => 0,
         })
     }
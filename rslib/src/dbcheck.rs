@@ -40,6 +40,36 @@ pub struct CheckDatabaseOutput {
     notetypes_recovered: usize,
     invalid_utf8: usize,
     invalid_ids: usize,
+    notes_missing_cards: usize,
+}
+
+/// A kind of problem found by [Collection::check_integrity].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityProblemKind {
+    NotetypesRecovered,
+    CardPositionTooHigh,
+    CardPropertiesInvalid,
+    CardsMissingNote,
+    DecksMissing,
+    FieldCountMismatch,
+    CardOrdsDuplicated,
+    TemplatesMissing,
+    RevlogPropertiesInvalid,
+    InvalidUtf8,
+    InvalidIds,
+    NotesMissingCards,
+}
+
+/// A problem found by [Collection::check_integrity].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegrityProblem {
+    pub kind: IntegrityProblemKind,
+    /// The number of affected objects.
+    pub count: usize,
+    /// Whether the problem was repaired. Every kind the check currently
+    /// detects is repaired as it is found (notes missing cards get one
+    /// generated), so this is always true for now.
+    pub fixed: bool,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -93,9 +123,40 @@ impl CheckDatabaseOutput {
         if self.invalid_ids > 0 {
             probs.push(tr.database_check_fixed_invalid_ids(self.invalid_ids));
         }
+        if self.notes_missing_cards > 0 {
+            probs.push(tr.database_check_notes_missing_cards(self.notes_missing_cards));
+        }
 
         probs.into_iter().map(Into::into).collect()
     }
+
+    /// The problems that were found, in the same order as
+    /// [CheckDatabaseOutput::to_i18n_strings].
+    pub fn problems(&self) -> Vec<IntegrityProblem> {
+        use IntegrityProblemKind::*;
+        [
+            (NotetypesRecovered, self.notetypes_recovered),
+            (CardPositionTooHigh, self.card_position_too_high),
+            (CardPropertiesInvalid, self.card_properties_invalid),
+            (CardsMissingNote, self.cards_missing_note),
+            (DecksMissing, self.decks_missing),
+            (FieldCountMismatch, self.field_count_mismatch),
+            (CardOrdsDuplicated, self.card_ords_duplicated),
+            (TemplatesMissing, self.templates_missing),
+            (RevlogPropertiesInvalid, self.revlog_properties_invalid),
+            (InvalidUtf8, self.invalid_utf8),
+            (InvalidIds, self.invalid_ids),
+            (NotesMissingCards, self.notes_missing_cards),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| IntegrityProblem {
+            kind,
+            count,
+            fixed: true,
+        })
+        .collect()
+    }
 }
 
 impl Collection {
@@ -119,6 +180,21 @@ impl Collection {
         self.transact_no_undo(|col| col.check_database_inner(progress))
     }
 
+    /// Check and fix the database like the "Check Database" action does, and
+    /// return the problems that were found in machine-readable form.
+    pub fn check_integrity(&mut self) -> Result<Vec<IntegrityProblem>> {
+        Ok(self.check_database()?.problems())
+    }
+
+    /// Returns review cards whose due number looks like a timestamp rather
+    /// than a day number, which some add-ons have been known to write. Day
+    /// numbers count from the collection's creation, so anything above
+    /// 100,000 (some 270 years) can't be valid. The cards are not modified;
+    /// the database check resets them to be due today.
+    pub fn find_invalid_due_cards(&self) -> Result<Vec<CardId>> {
+        self.storage.review_card_ids_with_invalid_due()
+    }
+
     fn check_database_inner(
         &mut self,
         mut progress: ThrottlingProgressHandler<DatabaseCheckProgress>,
@@ -181,12 +257,25 @@ impl Collection {
     fn check_missing_deck_ids(&mut self, out: &mut CheckDatabaseOutput) -> Result<()> {
         let usn = self.usn()?;
         for did in self.storage.missing_decks()? {
-            self.recover_missing_deck(did, usn)?;
+            let config_id = self.config_id_of_sibling_decks(did)?;
+            self.recover_missing_deck(did, config_id, usn)?;
             out.decks_missing += 1;
         }
         Ok(())
     }
 
+    /// The options group of the deck most siblings of the cards in `did` are
+    /// in, so a recovered deck keeps the options its notes were studied with.
+    fn config_id_of_sibling_decks(&self, did: DeckId) -> Result<Option<DeckConfigId>> {
+        for sibling_did in self.storage.sibling_deck_ids(did)? {
+            let deck = self.storage.get_deck(sibling_did)?;
+            if let Some(config_id) = deck.and_then(|deck| deck.config_id()) {
+                return Ok(Some(config_id));
+            }
+        }
+        Ok(None)
+    }
+
     fn check_filtered_cards(&mut self, out: &mut CheckDatabaseOutput) -> Result<()> {
         let decks = self.storage.get_decks_map()?;
 
@@ -259,6 +348,10 @@ impl Collection {
                 let original = note.clone();
 
                 let cards = self.storage.existing_cards_for_note(nid)?;
+                if cards.is_empty() {
+                    // a card will be generated below
+                    out.notes_missing_cards += 1;
+                }
 
                 out.card_ords_duplicated += self.remove_duplicate_card_ordinals(&cards)?;
                 out.templates_missing += self.remove_cards_without_template(&nt, &cards)?;
@@ -462,6 +555,8 @@ mod test {
     use super::*;
     use crate::decks::DeckId;
     use crate::search::SortMode;
+    use crate::tests::DeckAdder;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn cards() -> Result<()> {
@@ -525,6 +620,90 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn invalid_due_cards() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_rev_notetype();
+        let note = NoteAdder::new(&nt).fields(&["a", "b"]).add(&mut col);
+        let cids = col
+            .storage
+            .all_card_ids_of_note_in_template_order(note.id)?;
+        col.storage.db.execute(
+            "update cards set type = 2, queue = 2, due = 1700000000 where id = ?",
+            [cids[0]],
+        )?;
+        col.storage.db.execute(
+            "update cards set type = 2, queue = 2, due = 500 where id = ?",
+            [cids[1]],
+        )?;
+        assert_eq!(col.find_invalid_due_cards()?, [cids[0]]);
+        // nothing should have been changed
+        assert_eq!(col.storage.get_card(cids[0])?.unwrap().due, 1700000000);
+
+        col.check_database()?;
+        assert_eq!(col.find_invalid_due_cards()?, []);
+
+        Ok(())
+    }
+
+    #[test]
+    fn recovered_decks_keep_sibling_config() -> Result<()> {
+        let mut col = Collection::new();
+        let deck = DeckAdder::new("custom")
+            .with_config(|config| config.inner.new_per_day = 5)
+            .add(&mut col);
+        let note = NoteAdder::new(&col.basic_rev_notetype())
+            .fields(&["front", "back"])
+            .deck(deck.id)
+            .add(&mut col);
+        let cids = col
+            .storage
+            .all_card_ids_of_note_in_template_order(note.id)?;
+        col.storage
+            .db
+            .execute("update cards set did = 123 where id = ?", [cids[1]])?;
+
+        assert_eq!(col.check_database()?.decks_missing, 1);
+        let recovered = col.storage.get_deck(DeckId(123))?.unwrap();
+        assert_eq!(recovered.config_id(), deck.config_id());
+        assert_ne!(recovered.config_id(), Some(DeckConfigId(1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn integrity_problems() -> Result<()> {
+        let mut col = Collection::new();
+        let note = NoteAdder::basic(&mut col).add(&mut col);
+        assert_eq!(col.check_integrity()?, []);
+
+        for cid in col.storage.card_ids_of_notes(&[note.id])? {
+            col.storage.remove_card(cid)?;
+        }
+        col.storage
+            .db
+            .execute_batch("update notes set flds = 'one'")?;
+        assert_eq!(
+            col.check_integrity()?,
+            [
+                IntegrityProblem {
+                    kind: IntegrityProblemKind::FieldCountMismatch,
+                    count: 1,
+                    fixed: true,
+                },
+                IntegrityProblem {
+                    kind: IntegrityProblemKind::NotesMissingCards,
+                    count: 1,
+                    fixed: true,
+                },
+            ]
+        );
+        assert_eq!(col.storage.all_cards_of_note(note.id)?.len(), 1);
+        assert_eq!(col.check_integrity()?, []);
+
+        Ok(())
+    }
+
     #[test]
     fn revlog() -> Result<()> {
         let mut col = Collection::new();
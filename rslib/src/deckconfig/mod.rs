@@ -8,6 +8,7 @@ mod update;
 
 pub use anki_proto::deck_config::deck_config::config::AnswerAction;
 pub use anki_proto::deck_config::deck_config::config::LeechAction;
+pub use anki_proto::deck_config::deck_config::config::LimitOverride;
 pub use anki_proto::deck_config::deck_config::config::NewCardGatherPriority;
 pub use anki_proto::deck_config::deck_config::config::NewCardInsertOrder;
 pub use anki_proto::deck_config::deck_config::config::NewCardSortOrder;
@@ -43,6 +44,7 @@ const DEFAULT_DECK_CONFIG_INNER: DeckConfigInner = DeckConfigInner {
     relearn_steps: Vec::new(),
     new_per_day: 20,
     reviews_per_day: 200,
+    limit_override: None,
     new_per_day_minimum: 0,
     initial_ease: 2.5,
     easy_multiplier: 1.3,
@@ -56,6 +58,7 @@ const DEFAULT_DECK_CONFIG_INNER: DeckConfigInner = DeckConfigInner {
     new_card_insert_order: NewCardInsertOrder::Due as i32,
     new_card_gather_priority: NewCardGatherPriority::Deck as i32,
     new_card_sort_order: NewCardSortOrder::Template as i32,
+    new_card_sort_field: String::new(),
     review_order: ReviewCardOrder::Day as i32,
     new_mix: ReviewMix::MixWithReviews as i32,
     interday_learning_mix: ReviewMix::MixWithReviews as i32,
@@ -74,6 +77,9 @@ const DEFAULT_DECK_CONFIG_INNER: DeckConfigInner = DeckConfigInner {
     bury_new: false,
     bury_reviews: false,
     bury_interday_learning: false,
+    new_ignore_review_limit: false,
+    enable_load_balancer: false,
+    learning_step_fuzz: 0.0,
     fsrs_weights: vec![],
     desired_retention: 0.9,
     other: Vec::new(),
@@ -103,6 +109,29 @@ impl DeckConfig {
         self.mtime_secs = TimestampSecs::now();
         self.usn = usn;
     }
+
+    /// The new card limit for the given day, taking an unexpired override
+    /// into account.
+    pub(crate) fn new_limit_on_day(&self, today: u32) -> u32 {
+        self.limit_override_on_day(today)
+            .map(|over| over.new_limit)
+            .unwrap_or(self.inner.new_per_day)
+    }
+
+    /// The review limit for the given day, taking an unexpired override into
+    /// account.
+    pub(crate) fn review_limit_on_day(&self, today: u32) -> u32 {
+        self.limit_override_on_day(today)
+            .map(|over| over.review_limit)
+            .unwrap_or(self.inner.reviews_per_day)
+    }
+
+    fn limit_override_on_day(&self, today: u32) -> Option<&LimitOverride> {
+        self.inner
+            .limit_override
+            .as_ref()
+            .filter(|over| today <= over.expires_on_day)
+    }
 }
 
 impl Collection {
@@ -125,6 +154,17 @@ impl Collection {
             Ok(None)
         }
     }
+
+    /// The config group a normal deck uses, falling back to the default group
+    /// if the deck refers to a missing one. Filtered decks have their options
+    /// stored inline instead, and return an error.
+    pub fn resolved_deck_config(&self, did: DeckId) -> Result<DeckConfig> {
+        let deck = self.storage.get_deck(did)?.or_not_found(did)?;
+        let dcid = deck
+            .config_id()
+            .or_invalid("filtered decks don't use a config group")?;
+        Ok(self.get_deck_config(dcid, true)?.unwrap())
+    }
 }
 
 impl Collection {
@@ -289,6 +329,12 @@ pub(crate) fn ensure_deck_config_values_valid(config: &mut DeckConfigInner) {
         default.historical_retention,
         0.7,
         0.97,
+    );
+    ensure_f32_valid(
+        &mut config.learning_step_fuzz,
+        default.learning_step_fuzz,
+        0.0,
+        100.0,
     )
 }
 
@@ -303,3 +349,29 @@ fn ensure_u32_valid(val: &mut u32, default: u32, min: u32, max: u32) {
         *val = default;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::DeckAdder;
+
+    #[test]
+    fn resolved_deck_config() -> Result<()> {
+        let mut col = Collection::new();
+        let deck = DeckAdder::new("custom")
+            .with_config(|config| config.name = "custom".into())
+            .add(&mut col);
+        assert_eq!(col.resolved_deck_config(deck.id)?.name, "custom");
+
+        // a missing group falls back to the default one
+        let dcid = deck.config_id().unwrap();
+        col.storage.remove_deck_conf(dcid)?;
+        assert_eq!(col.resolved_deck_config(deck.id)?.id, DeckConfigId(1));
+
+        let filtered = DeckAdder::new("filtered").filtered(true).add(&mut col);
+        assert!(col.resolved_deck_config(filtered.id).is_err());
+        assert!(col.resolved_deck_config(DeckId(1234)).is_err());
+
+        Ok(())
+    }
+}
@@ -18,6 +18,7 @@ use serde_tuple::Serialize_tuple;
 use super::DeckConfig;
 use super::DeckConfigId;
 use super::DeckConfigInner;
+use super::LimitOverride;
 use super::NewCardInsertOrder;
 use super::INITIAL_EASE_FACTOR_THOUSANDS;
 use crate::serde::default_on_invalid;
@@ -65,9 +66,17 @@ pub struct DeckConfSchema11 {
     #[serde(default)]
     new_sort_order: i32,
     #[serde(default)]
+    new_sort_field: String,
+    #[serde(default)]
     new_gather_priority: i32,
     #[serde(default)]
     bury_interday_learning: bool,
+    #[serde(default)]
+    new_ignore_review_limit: bool,
+    #[serde(default)]
+    enable_load_balancer: bool,
+    #[serde(default)]
+    learning_step_fuzz: f32,
 
     #[serde(default)]
     fsrs_weights: Vec<f32>,
@@ -92,10 +101,40 @@ pub struct DeckConfSchema11 {
     sm2_retention: f32,
     #[serde(default)]
     weight_search: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limit_override: Option<LimitOverrideSchema11>,
 
     #[serde(flatten)]
     other: HashMap<String, Value>,
 }
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitOverrideSchema11 {
+    new_limit: u32,
+    review_limit: u32,
+    expires_on_day: u32,
+}
+
+impl From<LimitOverrideSchema11> for LimitOverride {
+    fn from(o: LimitOverrideSchema11) -> Self {
+        LimitOverride {
+            new_limit: o.new_limit,
+            review_limit: o.review_limit,
+            expires_on_day: o.expires_on_day,
+        }
+    }
+}
+
+impl From<LimitOverride> for LimitOverrideSchema11 {
+    fn from(o: LimitOverride) -> Self {
+        LimitOverrideSchema11 {
+            new_limit: o.new_limit,
+            review_limit: o.review_limit,
+            expires_on_day: o.expires_on_day,
+        }
+    }
+}
 #[derive(Serialize_repr, Deserialize_repr, Debug, PartialEq, Eq, Clone)]
 #[repr(u8)]
 #[derive(Default)]
@@ -302,13 +341,18 @@ impl Default for DeckConfSchema11 {
             interday_learning_mix: 0,
             review_order: 0,
             new_sort_order: 0,
+            new_sort_field: "".to_string(),
             new_gather_priority: 0,
             bury_interday_learning: false,
+            new_ignore_review_limit: false,
+            enable_load_balancer: false,
+            learning_step_fuzz: 0.0,
             fsrs_weights: vec![],
             desired_retention: 0.9,
             sm2_retention: 0.9,
             weight_search: "".to_string(),
             ignore_revlogs_before_date: "".to_string(),
+            limit_override: None,
         }
     }
 }
@@ -349,6 +393,7 @@ impl From<DeckConfSchema11> for DeckConfig {
                 relearn_steps: c.lapse.delays,
                 new_per_day: c.new.per_day,
                 reviews_per_day: c.rev.per_day,
+                limit_override: c.limit_override.map(Into::into),
                 new_per_day_minimum: c.new_per_day_minimum,
                 initial_ease: (c.new.initial_factor as f32) / 1000.0,
                 easy_multiplier: c.rev.ease4,
@@ -365,6 +410,7 @@ impl From<DeckConfSchema11> for DeckConfig {
                 } as i32,
                 new_card_gather_priority: c.new_gather_priority,
                 new_card_sort_order: c.new_sort_order,
+                new_card_sort_field: c.new_sort_field,
                 review_order: c.review_order,
                 new_mix: c.new_mix,
                 interday_learning_mix: c.interday_learning_mix,
@@ -383,6 +429,9 @@ impl From<DeckConfSchema11> for DeckConfig {
                 bury_new: c.new.bury,
                 bury_reviews: c.rev.bury,
                 bury_interday_learning: c.bury_interday_learning,
+                new_ignore_review_limit: c.new_ignore_review_limit,
+                enable_load_balancer: c.enable_load_balancer,
+                learning_step_fuzz: c.learning_step_fuzz,
                 fsrs_weights: c.fsrs_weights,
                 ignore_revlogs_before_date: c.ignore_revlogs_before_date,
                 desired_retention: c.desired_retention,
@@ -492,13 +541,18 @@ impl From<DeckConfig> for DeckConfSchema11 {
             interday_learning_mix: i.interday_learning_mix,
             review_order: i.review_order,
             new_sort_order: i.new_card_sort_order,
+            new_sort_field: i.new_card_sort_field,
             new_gather_priority: i.new_card_gather_priority,
             bury_interday_learning: i.bury_interday_learning,
+            new_ignore_review_limit: i.new_ignore_review_limit,
+            enable_load_balancer: i.enable_load_balancer,
+            learning_step_fuzz: i.learning_step_fuzz,
             fsrs_weights: i.fsrs_weights,
             desired_retention: i.desired_retention,
             sm2_retention: i.historical_retention,
             weight_search: i.weight_search,
             ignore_revlogs_before_date: i.ignore_revlogs_before_date,
+            limit_override: i.limit_override.map(Into::into),
         }
     }
 }
@@ -506,6 +560,7 @@ impl From<DeckConfig> for DeckConfSchema11 {
 static RESERVED_DECKCONF_KEYS: Set<&'static str> = phf_set! {
     "id",
     "newSortOrder",
+    "newSortField",
     "replayq",
     "newPerDayMinimum",
     "usn",
@@ -514,6 +569,9 @@ static RESERVED_DECKCONF_KEYS: Set<&'static str> = phf_set! {
     "maxTaken",
     "reviewOrder",
     "buryInterdayLearning",
+    "newIgnoreReviewLimit",
+    "enableLoadBalancer",
+    "learningStepFuzz",
     "newMix",
     "mod",
     "timer",
@@ -531,6 +589,7 @@ static RESERVED_DECKCONF_KEYS: Set<&'static str> = phf_set! {
     "sm2Retention",
     "weightSearch",
     "ignoreRevlogsBeforeDate",
+    "limitOverride",
 };
 
 static RESERVED_DECKCONF_NEW_KEYS: Set<&'static str> = phf_set! {
@@ -571,6 +630,25 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn limit_override_survives_schema11_roundtrip() -> Result<()> {
+        let mut config = DeckConfig::default();
+        config.inner.limit_override = Some(LimitOverride {
+            new_limit: 50,
+            review_limit: 500,
+            expires_on_day: 30,
+        });
+        let json = serde_json::to_value(DeckConfSchema11::from(config.clone()))?;
+        assert_eq!(json["limitOverride"]["expiresOnDay"], 30);
+        let s11: DeckConfSchema11 = serde_json::from_value(json)?;
+        assert_eq!(
+            DeckConfig::from(s11).inner.limit_override,
+            config.inner.limit_override
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn new_intervals() {
         let decode = |value: Value| -> NewCardIntervals {
@@ -14,6 +14,7 @@ use anki_proto::deck_config::UpdateDeckConfigsMode;
 use anki_proto::decks::deck::normal::DayLimit;
 use fsrs::DEFAULT_PARAMETERS;
 
+use crate::card::CardType;
 use crate::config::I32ConfigKey;
 use crate::config::StringKey;
 use crate::decks::NormalDeck;
@@ -294,6 +295,73 @@ impl Collection {
         Ok(())
     }
 
+    /// Store FSRS weights on a deck config, eg ones found by
+    /// [Collection::optimize_fsrs_parameters]. Cards' memory states are not
+    /// recalculated.
+    pub fn set_fsrs_parameters(
+        &mut self,
+        config_id: DeckConfigId,
+        params: &[f32],
+    ) -> Result<OpOutput<()>> {
+        if params.len() != DEFAULT_PARAMETERS.len() || !params.iter().all(|w| w.is_finite()) {
+            return Err(AnkiError::FsrsWeightsInvalid);
+        }
+        let original = self
+            .storage
+            .get_deck_config(config_id)?
+            .or_not_found(config_id)?;
+        let mut config = original.clone();
+        config.inner.fsrs_weights = params.to_vec();
+        let usn = Some(self.usn()?);
+        self.transact(Op::UpdateDeckConfig, |col| {
+            col.update_deck_config_inner(&mut config, original, usn)
+        })
+    }
+
+    /// Change the maximum review interval of a deck config. If
+    /// `clamp_existing` is true, review cards using the config whose interval
+    /// exceeds the new maximum have their interval and due date reduced to
+    /// it. Returns the number of cards that were clamped.
+    pub fn set_maximum_interval(
+        &mut self,
+        config_id: DeckConfigId,
+        days: u32,
+        clamp_existing: bool,
+    ) -> Result<OpOutput<usize>> {
+        require!(days > 0, "maximum interval must be at least one day");
+        let original = self
+            .storage
+            .get_deck_config(config_id)?
+            .or_not_found(config_id)?;
+        let mut config = original.clone();
+        config.inner.maximum_review_interval = days;
+        let usn = self.usn()?;
+        self.transact(Op::UpdateDeckConfig, |col| {
+            col.update_deck_config_inner(&mut config, original, Some(usn))?;
+            if !clamp_existing {
+                return Ok(0);
+            }
+            let search = SearchNode::PresetId(config_id).and(StateKind::Review);
+            let mut clamped = 0;
+            for mut card in col.all_cards_for_search(search)? {
+                if card.ctype != CardType::Review || card.interval <= days {
+                    continue;
+                }
+                let original = card.clone();
+                let due = if card.original_due != 0 {
+                    &mut card.original_due
+                } else {
+                    &mut card.due
+                };
+                *due -= (card.interval - days) as i32;
+                card.interval = days;
+                col.update_card_inner(&mut card, original, usn)?;
+                clamped += 1;
+            }
+            Ok(clamped)
+        })
+    }
+
     /// Adjust the remaining steps of cards in the given deck according to the
     /// config change.
     pub(crate) fn adjust_remaining_steps_in_deck(
@@ -414,10 +482,108 @@ fn update_day_limit(day_limit: &mut Option<DayLimit>, new_limit: Option<u32>, to
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::card::CardQueue;
     use crate::deckconfig::NewCardInsertOrder;
     use crate::tests::open_test_collection_with_learning_card;
     use crate::tests::open_test_collection_with_relearning_card;
 
+    #[test]
+    fn setting_fsrs_parameters() -> Result<()> {
+        let mut col = Collection::new();
+        let weights = |col: &Collection| {
+            col.get_deck_config(DeckConfigId(1), false)
+                .unwrap()
+                .unwrap()
+                .inner
+                .fsrs_weights
+        };
+        let mut params = DEFAULT_PARAMETERS.to_vec();
+        params[0] = 1.0;
+        col.set_fsrs_parameters(DeckConfigId(1), &params)?;
+        assert_eq!(weights(&col), params);
+
+        assert_eq!(
+            col.set_fsrs_parameters(DeckConfigId(1), &params[1..]),
+            Err(AnkiError::FsrsWeightsInvalid)
+        );
+        params[1] = f32::NAN;
+        assert!(col.set_fsrs_parameters(DeckConfigId(1), &params).is_err());
+        assert!(col
+            .set_fsrs_parameters(DeckConfigId(123), &DEFAULT_PARAMETERS)
+            .is_err());
+
+        col.undo()?;
+        assert!(weights(&col).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn setting_maximum_interval() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_rev_notetype();
+        for _ in 0..2 {
+            NoteAdder::new(&nt).fields(&["front", "back"]).add(&mut col);
+        }
+        let mut cards = col.all_cards_for_search("")?;
+        cards.sort_by_key(|c| c.id);
+        // two review cards, a relearning card and a new card
+        for (card, (ctype, interval)) in cards.iter_mut().zip([
+            (CardType::Review, 100),
+            (CardType::Review, 10),
+            (CardType::Relearn, 100),
+        ]) {
+            card.ctype = ctype;
+            card.queue = CardQueue::Review;
+            card.interval = interval;
+            card.due = 200;
+            col.storage.update_card(card)?;
+        }
+        // a review card using another preset with the same name
+        let other = DeckAdder::new("other")
+            .with_config(|config| config.name = "Default".into())
+            .add(&mut col);
+        let note = NoteAdder::basic(&mut col).deck(other.id).add(&mut col);
+        let mut other_card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+        other_card.ctype = CardType::Review;
+        other_card.queue = CardQueue::Review;
+        other_card.interval = 100;
+        other_card.due = 200;
+        col.storage.update_card(&other_card)?;
+
+        let max_interval = |col: &Collection| {
+            col.get_deck_config(DeckConfigId(1), false)
+                .unwrap()
+                .unwrap()
+                .inner
+                .maximum_review_interval
+        };
+        assert_eq!(
+            col.set_maximum_interval(DeckConfigId(1), 1000, false)?
+                .output,
+            0
+        );
+        assert_eq!(max_interval(&col), 1000);
+        assert!(col.set_maximum_interval(DeckConfigId(1), 0, true).is_err());
+
+        assert_eq!(
+            col.set_maximum_interval(DeckConfigId(1), 50, true)?.output,
+            1
+        );
+        assert_eq!(max_interval(&col), 50);
+        let card = col.storage.get_card(cards[0].id)?.unwrap();
+        assert_eq!((card.interval, card.due), (50, 150));
+        for card in cards[1..].iter().chain([&other_card]) {
+            assert_eq!(&col.storage.get_card(card.id)?.unwrap(), card);
+        }
+
+        col.undo()?;
+        assert_eq!(max_interval(&col), 1000);
+        assert_eq!(col.storage.get_card(cards[0].id)?.unwrap(), cards[0]);
+
+        Ok(())
+    }
+
     #[test]
     fn updating() -> Result<()> {
         let mut col = Collection::new();
@@ -4,15 +4,50 @@
 //! Adding and updating.
 
 use super::name::immediate_parent_name;
+use crate::config::I32ConfigKey;
 use crate::error::FilteredDeckError;
 use crate::prelude::*;
 
 impl Collection {
     /// Add a new deck. The id must be 0, as it will be automatically assigned.
-    pub fn add_deck(&mut self, deck: &mut Deck) -> Result<OpOutput<()>> {
+    /// Returns the names of any parent decks that had to be created, with
+    /// the topmost one first.
+    pub fn add_deck(&mut self, deck: &mut Deck) -> Result<OpOutput<Vec<NativeDeckName>>> {
         self.transact(Op::AddDeck, |col| col.add_deck_inner(deck, col.usn()?))
     }
 
+    /// Add multiple new decks in a single undoable operation. As with
+    /// add_deck(), ids must be 0. The returned ids are in the same order as
+    /// the provided decks.
+    pub fn add_decks(&mut self, decks: &mut [Deck]) -> Result<OpOutput<Vec<DeckId>>> {
+        self.transact(Op::AddDeck, |col| {
+            let usn = col.usn()?;
+            decks
+                .iter_mut()
+                .map(|deck| {
+                    col.add_deck_inner(deck, usn)?;
+                    Ok(deck.id)
+                })
+                .collect()
+        })
+    }
+
+    /// Add a new deck with a caller-provided id, creating any missing parents.
+    /// Returns [AnkiError::Existing] if the id is already taken.
+    pub fn add_deck_with_id(&mut self, deck: &mut Deck) -> Result<OpOutput<()>> {
+        require!(deck.id.0 != 0, "deck to add must have a non-zero id");
+        self.transact(Op::AddDeck, |col| {
+            if col.storage.get_deck(deck.id)?.is_some() {
+                return Err(AnkiError::Existing);
+            }
+            let usn = col.usn()?;
+            col.prepare_deck_for_update(deck, usn)?;
+            deck.set_modified(usn);
+            col.match_or_create_parents(deck, usn)?;
+            col.add_or_update_deck_with_existing_id_undoable(deck)
+        })
+    }
+
     pub fn update_deck(&mut self, deck: &mut Deck) -> Result<OpOutput<()>> {
         self.transact(Op::UpdateDeck, |col| {
             let existing_deck = col.storage.get_deck(deck.id)?.or_not_found(deck.id)?;
@@ -26,7 +61,7 @@ impl Collection {
     /// can integrate with older Python code that behaved this way.
     pub fn add_or_update_deck(&mut self, deck: &mut Deck) -> Result<OpOutput<()>> {
         if deck.id.0 == 0 {
-            self.add_deck(deck)
+            self.add_deck(deck).map(|out| out.map(|_| ()))
         } else {
             self.update_deck(deck)
         }
@@ -43,12 +78,18 @@ impl Collection {
         self.ensure_deck_name_unique(deck, usn)
     }
 
-    pub(crate) fn add_deck_inner(&mut self, deck: &mut Deck, usn: Usn) -> Result<()> {
+    /// Returns the names of any parents that were created.
+    pub(crate) fn add_deck_inner(
+        &mut self,
+        deck: &mut Deck,
+        usn: Usn,
+    ) -> Result<Vec<NativeDeckName>> {
         require!(deck.id.0 == 0, "deck to add must have id 0");
         self.prepare_deck_for_update(deck, usn)?;
         deck.set_modified(usn);
-        self.match_or_create_parents(deck, usn)?;
-        self.add_deck_undoable(deck)
+        let created_parents = self.match_or_create_parents(deck, usn)?;
+        self.add_deck_undoable(deck)?;
+        Ok(created_parents)
     }
 
     pub(crate) fn update_deck_inner(
@@ -63,19 +104,23 @@ impl Collection {
         }
         deck.set_modified(usn);
         let name_changed = original.name != deck.name;
-        if name_changed {
+        if name_changed && deck.name.eq_ignoring_case(&original.name) {
+            // parents can't have changed, so the name only needs to be
+            // reapplied to the children
+            deck.name = original.name.with_base_name_of(&deck.name);
+            if deck.name != original.name {
+                self.rename_child_decks(&original, &deck.name, usn)?;
+            }
+        } else if name_changed {
+            if deck.name.is_descendant_of(&original.name) {
+                invalid_input!("deck cannot be moved under itself");
+            }
             // match closest parent name
             self.match_or_create_parents(deck, usn)?;
             // rename children
             self.rename_child_decks(&original, &deck.name, usn)?;
         }
-        self.update_single_deck_undoable(deck, original)?;
-        if name_changed {
-            // after updating, we need to ensure all grandparents exist, which may not be
-            // the case in the parent->child case
-            self.create_missing_parents(&deck.name, usn)?;
-        }
-        Ok(())
+        self.update_single_deck_undoable(deck, original)
     }
 
     /// Add/update a single deck when syncing/importing. Ensures name is unique
@@ -90,10 +135,23 @@ impl Collection {
         self.add_or_update_deck_with_existing_id_undoable(deck)
     }
 
-    pub(crate) fn recover_missing_deck(&mut self, did: DeckId, usn: Usn) -> Result<()> {
+    /// Re-add a deck that is referenced by cards but missing. If a
+    /// `config_id` is provided and exists, the deck will use it instead of the
+    /// default options group.
+    pub(crate) fn recover_missing_deck(
+        &mut self,
+        did: DeckId,
+        config_id: Option<DeckConfigId>,
+        usn: Usn,
+    ) -> Result<()> {
         let mut deck = Deck::new_normal();
         deck.id = did;
         deck.name = NativeDeckName::from_native_str(format!("recovered{}", did));
+        if let Some(config_id) = config_id {
+            if self.storage.get_deck_config(config_id)?.is_some() {
+                deck.normal_mut()?.config_id = config_id.0;
+            }
+        }
         deck.set_modified(usn);
         self.add_or_update_single_deck_with_existing_id(&mut deck, usn)
     }
@@ -108,44 +166,42 @@ impl Collection {
     }
 
     /// If parent deck(s) exist, rewrite name to match their case.
-    /// If they don't exist, create them.
+    /// If they don't exist, create them, and return their names.
     /// Returns an error if a DB operation fails, or if the first existing
     /// parent is a filtered deck.
-    fn match_or_create_parents(&mut self, deck: &mut Deck, usn: Usn) -> Result<()> {
-        let child_split: Vec<_> = deck.name.components().collect();
-        if let Some(parent_deck) = self.first_existing_parent(deck.name.as_native_str(), 0)? {
-            if parent_deck.is_filtered() {
-                return Err(FilteredDeckError::MustBeLeafNode.into());
-            }
-            let parent_count = parent_deck.name.components().count();
-            let need_create = parent_count != child_split.len() - 1;
-            deck.name = NativeDeckName::from_native_str(format!(
-                "{}\x1f{}",
-                parent_deck.name,
-                &child_split[parent_count..].join("\x1f")
-            ));
-            if need_create {
-                self.create_missing_parents(&deck.name, usn)?;
-            }
-            Ok(())
-        } else if child_split.len() == 1 {
-            // no parents required
-            Ok(())
+    fn match_or_create_parents(
+        &mut self,
+        deck: &mut Deck,
+        usn: Usn,
+    ) -> Result<Vec<NativeDeckName>> {
+        let plan = self.plan_parents(deck.name.as_native_str())?;
+        deck.name = plan.name;
+        if plan.missing_parents.is_empty() {
+            Ok(vec![])
         } else {
-            // no existing parents
             self.create_missing_parents(&deck.name, usn)
         }
     }
 
-    fn create_missing_parents(&mut self, name: &NativeDeckName, usn: Usn) -> Result<()> {
+    /// Returns the names of the created decks, with the topmost one first.
+    fn create_missing_parents(
+        &mut self,
+        name: &NativeDeckName,
+        usn: Usn,
+    ) -> Result<Vec<NativeDeckName>> {
+        let max_depth = self.max_deck_depth();
+        let mut created = vec![];
         let mut machine_name = name.as_native_str();
         while let Some(parent_name) = immediate_parent_name(machine_name) {
             if self.storage.get_deck_id(parent_name)?.is_none() {
+                require!(created.len() <= max_depth, "deck nesting level too deep");
                 self.add_parent_deck(parent_name, usn)?;
+                created.push(NativeDeckName::from_native_str(parent_name));
             }
             machine_name = parent_name;
         }
-        Ok(())
+        created.reverse();
+        Ok(created)
     }
 
     pub(crate) fn first_existing_parent(
@@ -153,15 +209,277 @@ impl Collection {
         machine_name: &str,
         recursion_level: usize,
     ) -> Result<Option<Deck>> {
-        require!(recursion_level < 11, "deck nesting level too deep");
+        Ok(self
+            .existing_parent_inner(machine_name, recursion_level, self.max_deck_depth())?
+            .map(|parent| parent.deck))
+    }
+
+    fn existing_parent_inner(
+        &self,
+        machine_name: &str,
+        recursion_level: usize,
+        max_depth: usize,
+    ) -> Result<Option<ExistingParent>> {
+        require!(recursion_level <= max_depth, "deck nesting level too deep");
         if let Some(parent_name) = immediate_parent_name(machine_name) {
             if let Some(parent_did) = self.storage.get_deck_id(parent_name)? {
-                self.storage.get_deck(parent_did)
+                Ok(self
+                    .storage
+                    .get_deck(parent_did)?
+                    .map(|deck| ExistingParent {
+                        deck,
+                        depth: recursion_level,
+                    }))
             } else {
-                self.first_existing_parent(parent_name, recursion_level + 1)
+                self.existing_parent_inner(parent_name, recursion_level + 1, max_depth)
             }
         } else {
             Ok(None)
         }
     }
 }
+
+/// The nearest ancestor of a deck name that is present in the collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExistingParent {
+    pub deck: Deck,
+    /// The number of missing decks between the child and `deck`. 0 means
+    /// `deck` is the immediate parent.
+    pub depth: usize,
+}
+
+/// The changes adding a deck would make to its parents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentPlan {
+    /// The deck's name, with the casing of its existing parents applied.
+    pub name: NativeDeckName,
+    /// Parents that would need to be created, with the topmost one first.
+    pub missing_parents: Vec<NativeDeckName>,
+}
+
+impl Collection {
+    /// Return the closest existing ancestor of the provided '\x1f'-separated
+    /// name, or None if no ancestor exists. This does not modify the
+    /// collection, so filtered ancestors are returned instead of causing an
+    /// error.
+    pub fn closest_existing_parent(&self, machine_name: &str) -> Result<Option<ExistingParent>> {
+        self.existing_parent_inner(machine_name, 0, self.max_deck_depth())
+    }
+
+    /// Determine what adding a deck with the provided '\x1f'-separated name
+    /// would do to its parents, without modifying the collection. Returns
+    /// [FilteredDeckError::MustBeLeafNode] if the first existing parent is a
+    /// filtered deck.
+    pub fn plan_parents(&self, machine_name: &str) -> Result<ParentPlan> {
+        let child_split: Vec<_> = machine_name.split('\x1f').collect();
+        let (name, existing_count) =
+            if let Some(parent_deck) = self.first_existing_parent(machine_name, 0)? {
+                if parent_deck.is_filtered() {
+                    return Err(FilteredDeckError::MustBeLeafNode {
+                        deck_id: parent_deck.id,
+                        name: parent_deck.human_name(),
+                    }
+                    .into());
+                }
+                let parent_count = parent_deck.name.components().count();
+                let name = format!(
+                    "{}\x1f{}",
+                    parent_deck.name,
+                    &child_split[parent_count..].join("\x1f")
+                );
+                (name, parent_count)
+            } else {
+                (machine_name.to_string(), 0)
+            };
+        let components: Vec<_> = name.split('\x1f').collect();
+        let missing_parents = (existing_count + 1..components.len())
+            .map(|count| NativeDeckName::from_native_str(components[..count].join("\x1f")))
+            .collect();
+        Ok(ParentPlan {
+            name: NativeDeckName::from_native_str(name),
+            missing_parents,
+        })
+    }
+
+    /// The maximum number of missing ancestors that will be searched or
+    /// created when adding a deck, to guard against runaway recursion.
+    fn max_deck_depth(&self) -> usize {
+        self.get_config_i32(I32ConfigKey::MaxDeckDepth).max(0) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn closest_existing_parent() -> Result<()> {
+        let mut col = Collection::new();
+        assert_eq!(col.closest_existing_parent("foo\x1fbar")?, None);
+
+        DeckAdder::new("foo").add(&mut col);
+        let parent = col.closest_existing_parent("foo\x1fbar\x1fbaz")?.unwrap();
+        assert_eq!(parent.deck.name.as_native_str(), "foo");
+        assert_eq!(parent.depth, 1);
+
+        // filtered parents are returned, not rejected
+        DeckAdder::new("filtered").filtered(true).add(&mut col);
+        let parent = col.closest_existing_parent("filtered\x1fchild")?.unwrap();
+        assert!(parent.deck.is_filtered());
+        assert_eq!(parent.depth, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_decks() -> Result<()> {
+        let mut col = Collection::new();
+        let mut decks: Vec<_> = ["Math::Algebra", "Math::Geometry", "Other"]
+            .into_iter()
+            .map(|name| DeckAdder::new(name).deck())
+            .collect();
+        let ids = col.add_decks(&mut decks)?.output;
+        assert_eq!(ids, decks.iter().map(|deck| deck.id).collect::<Vec<_>>());
+        // parent is only created once
+        assert_eq!(
+            col.storage
+                .get_all_deck_names()?
+                .into_iter()
+                .map(|(_, name)| name)
+                .collect::<Vec<_>>(),
+            vec![
+                "Default",
+                "Math",
+                "Math::Algebra",
+                "Math::Geometry",
+                "Other"
+            ]
+        );
+
+        // the whole batch is a single undo step
+        col.undo()?;
+        assert_eq!(col.storage.get_all_deck_names()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_deck_depth() -> Result<()> {
+        let mut col = Collection::new();
+        let name = (0..15).map(|n| n.to_string()).join("::");
+        let mut deck = DeckAdder::new(&name).deck();
+        assert!(col.add_deck(&mut deck).is_err());
+
+        col.set_config_i32_inner(I32ConfigKey::MaxDeckDepth, 20)?;
+        col.add_deck(&mut deck)?;
+        assert_eq!(col.storage.get_all_deck_names()?.len(), 16);
+
+        Ok(())
+    }
+
+    #[test]
+    fn created_parents() -> Result<()> {
+        let mut col = Collection::new();
+        let mut deck = DeckAdder::new("Languages::Spanish::Verbs").deck();
+        let created = col.add_deck(&mut deck)?.output;
+        assert_eq!(
+            created.iter().map(|name| name.human_name()).collect_vec(),
+            ["Languages", "Languages::Spanish"]
+        );
+
+        let mut deck = DeckAdder::new("Languages::Spanish::Nouns").deck();
+        assert!(col.add_deck(&mut deck)?.output.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn case_only_rename() -> Result<()> {
+        let mut col = Collection::new();
+        let deck = DeckAdder::new("languages::spanish").add(&mut col);
+        for i in 0..500 {
+            DeckAdder::new(format!("languages::spanish::{i}")).add(&mut col);
+        }
+
+        // only the base name is affected; parent casing is preserved
+        col.rename_deck(deck.id, "Languages::Spanish")?;
+        let names = col.storage.get_all_deck_names()?;
+        assert_eq!(names.len(), 503);
+        assert_eq!(names[1].1, "languages");
+        assert!(names[2..]
+            .iter()
+            .all(|(_, name)| name.starts_with("languages::Spanish")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_parents() -> Result<()> {
+        let mut col = Collection::new();
+        DeckAdder::new("math").add(&mut col);
+        let plan = col.plan_parents("Math\x1fAlgebra\x1fLinear\x1fMatrices")?;
+        assert_eq!(plan.name.human_name(), "math::Algebra::Linear::Matrices");
+        assert_eq!(
+            plan.missing_parents
+                .iter()
+                .map(|name| name.human_name())
+                .collect_vec(),
+            ["math::Algebra", "math::Algebra::Linear"]
+        );
+        // nothing was added
+        assert_eq!(col.storage.get_all_deck_names()?.len(), 2);
+
+        assert!(col.plan_parents("top")?.missing_parents.is_empty());
+
+        let filtered = DeckAdder::new("filtered").filtered(true).add(&mut col);
+        assert_eq!(
+            col.plan_parents("filtered\x1fchild"),
+            Err(AnkiError::FilteredDeckError {
+                source: FilteredDeckError::MustBeLeafNode {
+                    deck_id: filtered.id,
+                    name: "filtered".into()
+                }
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_with_id() -> Result<()> {
+        let mut col = Collection::new();
+        let mut deck = DeckAdder::new("foo::bar").deck();
+        assert!(col.add_deck_with_id(&mut deck).is_err());
+
+        deck.id = DeckId(1234);
+        col.add_deck_with_id(&mut deck)?;
+        assert_eq!(col.get_deck_id("foo::bar")?, Some(DeckId(1234)));
+        assert!(col.get_deck_id("foo")?.is_some());
+
+        let mut other = DeckAdder::new("other").deck();
+        other.id = DeckId(1234);
+        assert_eq!(col.add_deck_with_id(&mut other), Err(AnkiError::Existing));
+
+        Ok(())
+    }
+
+    #[test]
+    fn recover_with_config() -> Result<()> {
+        let mut col = Collection::new();
+        let mut config = DeckConfig::default();
+        col.add_or_update_deck_config(&mut config)?;
+
+        col.recover_missing_deck(DeckId(100), Some(config.id), Usn(0))?;
+        let deck = col.storage.get_deck(DeckId(100))?.unwrap();
+        assert_eq!(deck.config_id(), Some(config.id));
+
+        // missing configs fall back to the default
+        col.recover_missing_deck(DeckId(101), Some(DeckConfigId(1234)), Usn(0))?;
+        let deck = col.storage.get_deck(DeckId(101))?.unwrap();
+        assert_eq!(deck.config_id(), Some(DeckConfigId(1)));
+
+        Ok(())
+    }
+}
@@ -95,11 +95,14 @@ impl RemainingLimits {
         normal: &NormalDeck,
         config: &DeckConfig,
     ) -> RemainingLimits {
-        let mut review_limit =
-            current_review_limit(normal, today).unwrap_or(config.inner.reviews_per_day) as i32;
-        let mut new_limit =
-            current_new_limit(normal, today).unwrap_or(config.inner.new_per_day) as i32;
+        let mut review_limit = current_review_limit(normal, today)
+            .unwrap_or_else(|| config.review_limit_on_day(today))
+            as i32;
+        let mut new_limit = current_new_limit(normal, today)
+            .unwrap_or_else(|| config.new_limit_on_day(today)) as i32;
         let (new_today_count, review_today_count) = deck.new_rev_counts(today);
+        let new_cards_ignore_review_limit =
+            new_cards_ignore_review_limit || config.inner.new_ignore_review_limit;
 
         review_limit -= review_today_count;
         new_limit -= new_today_count;
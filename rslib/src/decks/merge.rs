@@ -0,0 +1,148 @@
+// Copyright: Ankitects Pty Ltd and contributors
+// License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
+
+use crate::error::FilteredDeckError;
+use crate::prelude::*;
+
+impl Collection {
+    /// Move all cards in `source` into `target`, move `source`'s children
+    /// under `target`, then remove `source`. Children that clash with an
+    /// existing child of `target` are merged into it as well. Returns the
+    /// number of cards that were moved.
+    pub fn merge_decks(&mut self, source: DeckId, target: DeckId) -> Result<OpOutput<usize>> {
+        self.transact(Op::MergeDecks, |col| col.merge_decks_inner(source, target))
+    }
+
+    fn merge_decks_inner(&mut self, source: DeckId, target: DeckId) -> Result<usize> {
+        require!(source != target, "can't merge a deck into itself");
+        let usn = self.usn()?;
+        let source = self.storage.get_deck(source)?.or_not_found(source)?;
+        let target = self.storage.get_deck(target)?.or_not_found(target)?;
+        if source.is_filtered() || target.is_filtered() {
+            return Err(FilteredDeckError::CanNotMoveCardsInto.into());
+        }
+        require!(
+            !target
+                .name
+                .as_native_str()
+                .starts_with(&format!("{}\x1f", source.name)),
+            "can't merge a deck into its own child"
+        );
+
+        let mut count = self.move_all_cards_to_deck(source.id, target.id, usn)?;
+        // children are returned in preorder, so parents are handled before their
+        // descendants
+        for mut child in self.storage.child_decks(&source)? {
+            let original = child.clone();
+            child.name.reparent(&source.name, &target.name);
+            match self.storage.get_deck_id(child.name.as_native_str())? {
+                Some(existing) if !child.is_filtered() && !self.deck_is_filtered(existing)? => {
+                    count += self.move_all_cards_to_deck(child.id, existing, usn)?;
+                    self.remove_single_deck(&original, usn)?;
+                }
+                _ => {
+                    self.ensure_deck_name_unique(&mut child, usn)?;
+                    child.set_modified(usn);
+                    self.update_single_deck_undoable(&mut child, original)?;
+                }
+            }
+        }
+        self.remove_single_deck(&source, usn)?;
+
+        Ok(count)
+    }
+
+    /// Reassign cards in or originating from `from` to `to`. Cards in a
+    /// filtered deck stay there, but will return to `to`.
+    fn move_all_cards_to_deck(&mut self, from: DeckId, to: DeckId, usn: Usn) -> Result<usize> {
+        let cids = self.storage.all_cards_in_single_deck(from)?;
+        for mut card in self.all_cards_for_ids(&cids, false)? {
+            let original = card.clone();
+            if card.original_deck_id == from {
+                card.original_deck_id = to;
+            } else {
+                card.deck_id = to;
+            }
+            self.update_card_inner(&mut card, original, usn)?;
+        }
+        Ok(cids.len())
+    }
+
+    fn deck_is_filtered(&self, did: DeckId) -> Result<bool> {
+        Ok(self
+            .storage
+            .get_deck(did)?
+            .map(|deck| deck.is_filtered())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn deck_names(col: &Collection) -> Vec<String> {
+        col.storage
+            .get_all_deck_names()
+            .unwrap()
+            .into_iter()
+            .map(|d| d.1)
+            .collect()
+    }
+
+    #[test]
+    fn merging() -> Result<()> {
+        let mut col = Collection::new();
+        let source = DeckAdder::new("Old::Spanish").add(&mut col);
+        let verbs = DeckAdder::new("Old::Spanish::Verbs").add(&mut col);
+        let nouns = DeckAdder::new("Old::Spanish::Nouns").add(&mut col);
+        let target = DeckAdder::new("Spanish").add(&mut col);
+        let target_nouns = DeckAdder::new("Spanish::Nouns").add(&mut col);
+        NoteAdder::basic(&mut col).deck(source.id).add(&mut col);
+        NoteAdder::basic(&mut col).deck(verbs.id).add(&mut col);
+        NoteAdder::basic(&mut col).deck(nouns.id).add(&mut col);
+
+        // can't merge into a child
+        assert!(col.merge_decks(source.id, verbs.id).is_err());
+
+        // cards of the source and the clashing child are moved
+        assert_eq!(col.merge_decks(source.id, target.id)?.output, 2);
+        assert_eq!(
+            deck_names(&col),
+            vec![
+                "Default",
+                "Old",
+                "Spanish",
+                "Spanish::Nouns",
+                "Spanish::Verbs"
+            ]
+        );
+        let mut dids: Vec<_> = col
+            .storage
+            .get_all_cards()
+            .into_iter()
+            .map(|card| card.deck_id)
+            .collect();
+        dids.sort();
+        let mut expected = vec![verbs.id, target.id, target_nouns.id];
+        expected.sort();
+        assert_eq!(dids, expected);
+
+        // the whole operation can be undone in one step
+        col.undo()?;
+        assert_eq!(
+            deck_names(&col),
+            vec![
+                "Default",
+                "Old",
+                "Old::Spanish",
+                "Old::Spanish::Nouns",
+                "Old::Spanish::Verbs",
+                "Spanish",
+                "Spanish::Nouns"
+            ]
+        );
+
+        Ok(())
+    }
+}
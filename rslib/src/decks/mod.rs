@@ -6,6 +6,7 @@ mod counts;
 mod current;
 pub mod filtered;
 pub(crate) mod limits;
+mod merge;
 mod name;
 mod remove;
 mod reparent;
@@ -17,6 +18,8 @@ pub(crate) mod undo;
 
 use std::sync::Arc;
 
+pub use addupdate::ExistingParent;
+pub use addupdate::ParentPlan;
 pub use anki_proto::decks::deck::filtered::search_term::Order as FilteredSearchOrder;
 pub use anki_proto::decks::deck::filtered::SearchTerm as FilteredSearchTerm;
 pub use anki_proto::decks::deck::kind_container::Kind as DeckKind;
@@ -33,6 +36,7 @@ pub use schema11::DeckSchema11;
 use crate::define_newtype;
 use crate::error::FilteredDeckError;
 use crate::markdown::render_markdown;
+use crate::ops::StateChanges;
 use crate::prelude::*;
 use crate::text::sanitize_html_no_images;
 
@@ -79,6 +83,15 @@ impl Deck {
         }
     }
 
+    /// Create a normal deck from a '::'-separated name, normalizing its
+    /// components. The name may not contain the native '\x1f' separator.
+    pub fn new_normal_from_human_name(name: &str) -> Result<Deck> {
+        require!(!name.contains('\x1f'), "deck name must not contain \\x1f");
+        let mut deck = Deck::new_normal();
+        deck.name = NativeDeckName::from_human_name(name);
+        Ok(deck)
+    }
+
     /// Returns deck config ID if deck is a normal deck.
     pub fn config_id(&self) -> Option<DeckConfigId> {
         if let DeckKind::Normal(ref norm) = self.kind {
@@ -158,6 +171,26 @@ impl Collection {
     }
 }
 
+impl Collection {
+    /// Return the id of the normal deck with the provided human name, adding
+    /// it and any missing parents if it doesn't exist yet. An existing deck
+    /// is returned without opening a transaction.
+    pub fn get_or_create_deck(&mut self, human_name: &str) -> Result<OpOutput<DeckId>> {
+        if let Some(did) = self.get_deck_id(human_name)? {
+            return Ok(OpOutput {
+                output: did,
+                changes: OpChanges {
+                    op: Op::AddDeck,
+                    changes: StateChanges::default(),
+                },
+            });
+        }
+        let mut deck = Deck::new_normal();
+        deck.name = NativeDeckName::from_human_name(human_name);
+        self.add_deck(&mut deck).map(|out| out.map(|_| deck.id))
+    }
+}
+
 impl Collection {
     pub fn get_deck(&mut self, did: DeckId) -> Result<Option<Arc<Deck>>> {
         if let Some(deck) = self.state.deck_cache.get(&did) {
@@ -172,6 +205,21 @@ impl Collection {
         }
     }
 
+    /// The provided deck's id, followed by the ids of its descendants if
+    /// `include_subdecks` is true.
+    pub(crate) fn deck_ids_maybe_with_children(
+        &self,
+        did: DeckId,
+        include_subdecks: bool,
+    ) -> Result<Vec<DeckId>> {
+        let deck = self.storage.get_deck(did)?.or_not_found(did)?;
+        if include_subdecks {
+            self.storage.deck_id_with_children(&deck)
+        } else {
+            Ok(vec![did])
+        }
+    }
+
     pub(crate) fn default_deck_is_empty(&self) -> Result<bool> {
         self.storage.deck_is_empty(DeckId(1))
     }
@@ -243,11 +291,13 @@ mod test {
         // add another child
         let _ = col.get_or_create_normal_deck("quux::foo::baz2");
 
-        // quux::foo -> quux::foo::baz::four
-        // means quux::foo::baz2 should be quux::foo::baz::four::baz2
-        // and a new quux::foo should have been created
+        // quux::foo -> quux::foo::baz::four would move the deck under itself
         middle.name = NativeDeckName::from_native_str("quux\x1ffoo\x1fbaz\x1ffour");
-        col.add_or_update_deck(&mut middle)?;
+        let err = col.add_or_update_deck(&mut middle).unwrap_err();
+        assert!(matches!(
+            err,
+            AnkiError::InvalidInput { source } if source.message == "deck cannot be moved under itself"
+        ));
         assert_eq!(
             sorted_names(&col),
             vec![
@@ -256,9 +306,7 @@ mod test {
                 "quux",
                 "quux::foo",
                 "quux::foo::baz",
-                "quux::foo::baz::four",
-                "quux::foo::baz::four::baz",
-                "quux::foo::baz::four::baz2"
+                "quux::foo::baz2"
             ]
         );
 
@@ -279,14 +327,49 @@ mod test {
                 "one::two::baz2",
                 "other",
                 "quux",
-                "quux::foo",
-                "quux::foo::baz",
             ]
         );
 
         Ok(())
     }
 
+    #[test]
+    fn renaming_under_itself() -> Result<()> {
+        let mut col = Collection::new();
+        let deck = col.get_or_create_normal_deck("A")?;
+        let err = col.rename_deck(deck.id, "A::A").unwrap_err();
+        assert!(matches!(
+            err,
+            AnkiError::InvalidInput { source } if source.message == "deck cannot be moved under itself"
+        ));
+        assert_eq!(sorted_names(&col), vec!["A", "Default"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_human_name() -> Result<()> {
+        let deck = Deck::new_normal_from_human_name("foo::bar:: baz")?;
+        assert_eq!(deck.name.as_native_str(), "foo\x1fbar\x1fbaz");
+        assert!(Deck::new_normal_from_human_name("foo\x1fbar").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_or_create() -> Result<()> {
+        let mut col = Collection::new();
+        let out = col.get_or_create_deck("foo::bar")?;
+        assert!(out.changes.had_change());
+        assert_eq!(sorted_names(&col), vec!["Default", "foo", "foo::bar"]);
+
+        let existing = col.get_or_create_deck("FOO::BAR")?;
+        assert_eq!(existing.output, out.output);
+        assert!(!existing.changes.had_change());
+
+        Ok(())
+    }
+
     #[test]
     fn default() -> Result<()> {
         // deleting the default deck will remove cards, but bring the deck back
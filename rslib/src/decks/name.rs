@@ -3,6 +3,8 @@
 use std::borrow::Cow;
 
 use itertools::Itertools;
+use tracing::warn;
+use unicase::eq as uni_eq;
 
 use crate::prelude::*;
 use crate::text::normalize_to_nfc;
@@ -80,9 +82,32 @@ impl NativeDeckName {
         }
     }
 
+    /// True if `other` is a (case-insensitive) ancestor of self.
+    pub(crate) fn is_descendant_of(&self, other: &NativeDeckName) -> bool {
+        let mut own_components = self.components();
+        other
+            .components()
+            .all(|comp| own_components.next().map_or(false, |own| uni_eq(own, comp)))
+            && own_components.next().is_some()
+    }
+
+    /// True if the names are equal when ignoring case.
+    pub(crate) fn eq_ignoring_case(&self, other: &NativeDeckName) -> bool {
+        uni_eq(&self.0, &other.0)
+    }
+
+    /// Return a copy of self with the last component taken from `other`.
+    pub(super) fn with_base_name_of(&self, other: &NativeDeckName) -> NativeDeckName {
+        let base = other.0.rsplit('\x1f').next().unwrap();
+        match immediate_parent_name(&self.0) {
+            Some(parent) => NativeDeckName(format!("{parent}\x1f{base}")),
+            None => NativeDeckName(base.into()),
+        }
+    }
+
     /// Replace the old parent's name with the new parent's name in self's name,
     /// where the old parent's name is expected to be a prefix.
-    fn reparent(&mut self, old_parent: &NativeDeckName, new_parent: &NativeDeckName) {
+    pub(super) fn reparent(&mut self, old_parent: &NativeDeckName, new_parent: &NativeDeckName) {
         self.0 = std::iter::once(new_parent.as_native_str())
             .chain(self.components().skip(old_parent.components().count()))
             .join("\x1f")
@@ -151,15 +176,24 @@ impl Collection {
     }
 
     pub(crate) fn ensure_deck_name_unique(&self, deck: &mut Deck, usn: Usn) -> Result<()> {
+        let mut original_name = None;
         loop {
             match self.storage.get_deck_id(deck.name.as_native_str())? {
                 Some(did) if did == deck.id => break,
                 None => break,
                 _ => (),
             }
+            original_name.get_or_insert_with(|| deck.human_name());
             deck.name.add_suffix("+");
             deck.set_modified(usn);
         }
+        if let Some(original_name) = original_name {
+            warn!(
+                from = original_name.as_str(),
+                to = deck.human_name().as_str(),
+                "deck renamed to be unique"
+            );
+        }
 
         Ok(())
     }
@@ -240,6 +274,20 @@ mod test {
         assert_eq!(native_name("foo:::bar:baz: "), "foo\x1fbar:baz");
     }
 
+    #[test]
+    fn descendant() {
+        fn is_descendant(child: &str, parent: &str) -> bool {
+            NativeDeckName::from_human_name(child)
+                .is_descendant_of(&NativeDeckName::from_human_name(parent))
+        }
+
+        assert!(is_descendant("foo::bar", "foo"));
+        assert!(is_descendant("FOO::bar::baz", "foo"));
+        assert!(!is_descendant("foo", "foo"));
+        assert!(!is_descendant("foo", "foo::bar"));
+        assert!(!is_descendant("foobar::baz", "foo"));
+    }
+
     #[test]
     fn normalize() {
         fn normalize_res(name: &str) -> (bool, String) {
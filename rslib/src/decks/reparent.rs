@@ -25,7 +25,11 @@ impl Collection {
         if let Some(target) = new_parent {
             if let Some(target) = self.storage.get_deck(target)? {
                 if target.is_filtered() {
-                    return Err(FilteredDeckError::MustBeLeafNode.into());
+                    return Err(FilteredDeckError::MustBeLeafNode {
+                        deck_id: target.id,
+                        name: target.human_name(),
+                    }
+                    .into());
                 }
                 target_deck = target;
                 target_name = Some(&target_deck.name);
@@ -165,6 +165,8 @@ pub struct FilteredDeckSchema11 {
     preview_hard_secs: u32,
     #[serde(default)]
     preview_good_secs: u32,
+    #[serde(default)]
+    preserve_manual_order: bool,
 }
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
 pub struct DeckTodaySchema11 {
@@ -339,6 +341,7 @@ impl From<FilteredDeckSchema11> for FilteredDeck {
             preview_again_secs: deck.preview_again_secs,
             preview_hard_secs: deck.preview_hard_secs,
             preview_good_secs: deck.preview_good_secs,
+            preserve_manual_order: deck.preserve_manual_order,
         }
     }
 }
@@ -381,6 +384,7 @@ impl From<Deck> for DeckSchema11 {
                 preview_again_secs: filt.preview_again_secs,
                 preview_hard_secs: filt.preview_hard_secs,
                 preview_good_secs: filt.preview_good_secs,
+                preserve_manual_order: filt.preserve_manual_order,
                 common: deck.into(),
             }),
         }
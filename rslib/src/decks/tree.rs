@@ -179,6 +179,28 @@ fn sum_counts_and_apply_limits_v3(
     this_node_capped
 }
 
+/// Sum the counts of each node and its descendants, without applying any
+/// limits.
+fn sum_counts_uncapped(node: &mut DeckTreeNode) -> NodeCountsV3 {
+    let mut counts = NodeCountsV3 {
+        new: node.new_count,
+        review: node.review_count,
+        intraday_learning: node.intraday_learning,
+        interday_learning: node.interday_learning_uncapped,
+        total: node.total_in_deck,
+    };
+    for child in &mut node.children {
+        counts += sum_counts_uncapped(child);
+    }
+
+    node.new_count = counts.new;
+    node.review_count = counts.review;
+    node.learn_count = counts.intraday_learning + counts.interday_learning;
+    node.total_including_children = counts.total;
+
+    counts
+}
+
 fn hide_default_deck(node: &mut DeckTreeNode) {
     for (idx, child) in node.children.iter().enumerate() {
         // we can hide the default if it has no children
@@ -289,6 +311,34 @@ impl Collection {
         Ok(tree)
     }
 
+    /// Get the deck tree with the due counts for the provided day. Unlike
+    /// [Collection::deck_tree], daily limits are not applied, so the new,
+    /// review and learn counts of each node are the plain sums of its own and
+    /// its descendants' cards. Cards are counted in the deck they are currently
+    /// in, so cards in a filtered deck do not contribute to their home deck.
+    /// For days other than the current one, learning cards are counted if
+    /// they are due before that day's rollover.
+    pub fn deck_tree_with_counts(&mut self, today: u32) -> Result<DeckTreeNode> {
+        let names = self.storage.get_all_deck_names()?;
+        let mut tree = deck_names_to_tree(names.into_iter());
+        let decks_map = self.storage.get_decks_map()?;
+        add_collapsed_and_filtered(&mut tree, &decks_map, false);
+
+        let timing = self.timing_today()?;
+        let learn_cutoff = if today == timing.days_elapsed {
+            (timing.now.0 as u32) + self.learn_ahead_secs()
+        } else {
+            // learning cards due by the end of the provided day
+            let days_from_today = today as i64 - timing.days_elapsed as i64;
+            timing.next_day_at.adding_secs(days_from_today * 86_400).0 as u32
+        };
+        let counts = self.due_counts(today, learn_cutoff)?;
+        add_counts(&mut tree, &counts);
+        sum_counts_uncapped(&mut tree);
+
+        Ok(tree)
+    }
+
     pub fn current_deck_tree(&mut self) -> Result<Option<DeckTreeNode>> {
         let target = self.get_current_deck_id();
         let tree = self.deck_tree(Some(TimestampSecs::now()))?;
@@ -345,6 +395,7 @@ mod test {
     use super::*;
     use crate::deckconfig::DeckConfigId;
     use crate::error::Result;
+    use crate::tests::open_test_collection_with_learning_card;
 
     #[test]
     fn wellformed() -> Result<()> {
@@ -472,4 +523,53 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn rolled_up_counts() -> Result<()> {
+        let mut col = Collection::new();
+        let algebra = DeckAdder::new("Math::Algebra").add(&mut col);
+        let geometry = DeckAdder::new("Math::Geometry").add(&mut col);
+        CardAdder::new().siblings(2).deck(algebra.id).add(&mut col);
+        CardAdder::new().deck(geometry.id).add(&mut col);
+
+        let today = col.timing_today()?.days_elapsed;
+        let tree = col.deck_tree_with_counts(today)?;
+        let math = tree.children.iter().find(|n| n.name == "Math").unwrap();
+        assert_eq!(math.new_count, 3);
+        assert_eq!(math.total_including_children, 3);
+        assert_eq!(math.total_in_deck, 0);
+        assert_eq!(math.children[0].new_count, 2);
+        assert_eq!(math.children[1].new_count, 1);
+
+        // cards moved into a filtered deck are only counted there
+        let mut filtered = col.get_or_create_filtered_deck(DeckId(0))?;
+        filtered.config.search_terms.truncate(1);
+        filtered.config.search_terms[0].search = "deck:Math::Algebra".into();
+        col.add_or_update_filtered_deck(filtered)?;
+        let tree = col.deck_tree_with_counts(today)?;
+        let math = tree.children.iter().find(|n| n.name == "Math").unwrap();
+        assert_eq!(math.new_count, 1);
+        assert_eq!(sum_deck_tree_node(&tree, |node| node.total_in_deck), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rolled_up_learn_counts_follow_provided_day() -> Result<()> {
+        let mut col = open_test_collection_with_learning_card();
+        // due later, but outside the learn ahead limit
+        let due = TimestampSecs::now().adding_secs(7200);
+        col.storage
+            .db
+            .execute_batch(&format!("UPDATE cards SET due = {}", due.0))?;
+        let today = col.timing_today()?.days_elapsed;
+        let learn_count = |col: &mut Collection, day| {
+            col.deck_tree_with_counts(day)
+                .map(|tree| sum_deck_tree_node(&tree, |node| node.learn_count))
+        };
+        assert_eq!(learn_count(&mut col, today)?, 0);
+        assert_eq!(learn_count(&mut col, today + 1)?, 1);
+
+        Ok(())
+    }
 }
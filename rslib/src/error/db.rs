@@ -42,6 +42,9 @@ impl AnkiError {
 impl From<Error> for AnkiError {
     fn from(err: Error) -> Self {
         if let Error::SqliteFailure(error, Some(reason)) = &err {
+            if error.code == rusqlite::ErrorCode::OperationInterrupted {
+                return AnkiError::Interrupted;
+            }
             if error.code == rusqlite::ErrorCode::DatabaseBusy {
                 return AnkiError::DbError {
                     source: DbError {
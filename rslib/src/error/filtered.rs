@@ -4,9 +4,15 @@
 use anki_i18n::I18n;
 use snafu::Snafu;
 
+use crate::decks::DeckId;
+
 #[derive(Debug, PartialEq, Eq, Snafu)]
 pub enum FilteredDeckError {
-    MustBeLeafNode,
+    /// A filtered deck would have become a parent.
+    MustBeLeafNode {
+        deck_id: DeckId,
+        name: String,
+    },
     CanNotMoveCardsInto,
     SearchReturnedNoCards,
     FilteredDeckRequired,
@@ -15,7 +21,7 @@ pub enum FilteredDeckError {
 impl FilteredDeckError {
     pub fn message(&self, tr: &I18n) -> String {
         match self {
-            FilteredDeckError::MustBeLeafNode => tr.errors_filtered_parent_deck(),
+            FilteredDeckError::MustBeLeafNode { .. } => tr.errors_filtered_parent_deck(),
             FilteredDeckError::CanNotMoveCardsInto => {
                 tr.browsing_cards_cant_be_manually_moved_into()
             }
@@ -31,6 +31,8 @@ pub use self::not_found::NotFoundError;
 pub use self::not_found::OrNotFound;
 use crate::import_export::ImportError;
 use crate::links::HelpPage;
+use crate::notetype::NotetypeId;
+use crate::template::localized_template_error;
 
 pub type Result<T, E = AnkiError> = std::result::Result<T, E>;
 
@@ -139,7 +141,15 @@ impl AnkiError {
                 let header =
                     tr.card_templates_invalid_template_number(source.ordinal + 1, &source.notetype);
                 let details = match &source.source {
-                    CardTypeErrorDetails::TemplateParseError => tr.card_templates_see_preview(),
+                    CardTypeErrorDetails::TemplateParseError { question, error } => {
+                        let side = if *question {
+                            tr.card_template_rendering_front_side_problem()
+                        } else {
+                            tr.card_template_rendering_back_side_problem()
+                        };
+                        let details = localized_template_error(tr, error.clone());
+                        format!("{side}<br>{}", htmlescape::encode_minimal(&details)).into()
+                    }
                     CardTypeErrorDetails::NoSuchField { field } => {
                         tr.card_templates_field_not_found(field)
                     }
@@ -198,7 +208,7 @@ impl AnkiError {
             Self::CardTypeError {
                 source: CardTypeError { source, .. },
             } => Some(match source {
-                CardTypeErrorDetails::TemplateParseError => HelpPage::CardTypeTemplateError,
+                CardTypeErrorDetails::TemplateParseError { .. } => HelpPage::CardTypeTemplateError,
                 CardTypeErrorDetails::NoSuchField { field: _ } => HelpPage::CardTypeTemplateError,
                 CardTypeErrorDetails::Duplicate { .. } => HelpPage::CardTypeDuplicate,
                 CardTypeErrorDetails::NoFrontField => HelpPage::CardTypeNoFrontField,
@@ -235,13 +245,22 @@ impl AnkiError {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Parse errors include the byte offset of the offending tag in the template
+/// text.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TemplateError {
-    NoClosingBrackets(String),
-    ConditionalNotClosed(String),
+    NoClosingBrackets {
+        tag: String,
+        offset: usize,
+    },
+    ConditionalNotClosed {
+        tag: String,
+        offset: usize,
+    },
     ConditionalNotOpen {
         closed: String,
         currently_open: Option<String>,
+        offset: usize,
     },
     FieldNotFound {
         filters: String,
@@ -311,6 +330,7 @@ impl From<std::io::Error> for AnkiError {
 #[snafu(visibility(pub))]
 pub struct CardTypeError {
     pub notetype: String,
+    pub notetype_id: NotetypeId,
     pub ordinal: usize,
     pub source: CardTypeErrorDetails,
 }
@@ -318,10 +338,17 @@ pub struct CardTypeError {
 #[derive(Debug, PartialEq, Eq, Snafu)]
 #[snafu(visibility(pub))]
 pub enum CardTypeErrorDetails {
-    TemplateParseError,
-    Duplicate { index: usize },
+    TemplateParseError {
+        question: bool,
+        error: TemplateError,
+    },
+    Duplicate {
+        index: usize,
+    },
     NoFrontField,
-    NoSuchField { field: String },
+    NoSuchField {
+        field: String,
+    },
     MissingCloze,
     ExtraneousCloze,
 }
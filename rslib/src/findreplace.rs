@@ -3,6 +3,7 @@
 
 use std::borrow::Cow;
 
+use lazy_static::lazy_static;
 use regex::Regex;
 
 use crate::collection::Collection;
@@ -32,10 +33,13 @@ impl FindReplaceContext {
         repl: impl Into<String>,
         field_name: Option<String>,
     ) -> Result<Self> {
+        let search = Regex::new(search_re)?;
+        let replacement = repl.into();
+        check_group_references(&search, &replacement)?;
         Ok(FindReplaceContext {
             nids,
-            search: Regex::new(search_re)?,
-            replacement: repl.into(),
+            search,
+            replacement,
             field_name,
         })
     }
@@ -45,13 +49,39 @@ impl FindReplaceContext {
     }
 }
 
+/// Ensure every `$1`/`${name}` group referenced by the replacement exists in
+/// the search, as the regex crate silently replaces unknown groups with an
+/// empty string.
+fn check_group_references(search: &Regex, replacement: &str) -> Result<()> {
+    lazy_static! {
+        static ref GROUP_REF: Regex = Regex::new(r"\$(?:\$|\{([^}]+)\}|([_0-9A-Za-z]+))").unwrap();
+    }
+    for caps in GROUP_REF.captures_iter(replacement) {
+        let Some(group) = caps.get(1).or_else(|| caps.get(2)).map(|m| m.as_str()) else {
+            // escaped dollar sign
+            continue;
+        };
+        let exists = match group.parse::<usize>() {
+            Ok(idx) => idx < search.captures_len(),
+            Err(_) => search.capture_names().flatten().any(|name| name == group),
+        };
+        if !exists {
+            invalid_input!("replacement refers to unknown group: {group}");
+        }
+    }
+    Ok(())
+}
+
 impl Collection {
+    /// Replace matches of `search_re` in the provided notes. If `field_name`
+    /// is provided, only that field is changed, and notes whose notetype
+    /// lacks the field are skipped.
     pub fn find_and_replace(
         &mut self,
         nids: Vec<NoteId>,
         search_re: &str,
         repl: &str,
-        field_name: Option<String>,
+        field_name: Option<&str>,
     ) -> Result<OpOutput<usize>> {
         self.transact(Op::FindAndReplace, |col| {
             let norm = col.get_config_bool(BoolKey::NormalizeNoteText);
@@ -60,7 +90,7 @@ impl Collection {
             } else {
                 search_re.into()
             };
-            let ctx = FindReplaceContext::new(nids, &search, repl, field_name)?;
+            let ctx = FindReplaceContext::new(nids, &search, repl, field_name.map(Into::into))?;
             col.find_and_replace_inner(ctx)
         })
     }
@@ -151,7 +181,7 @@ mod test {
                 "Text".into()
             ]
         );
-        let out = col.find_and_replace(nids, "BBB", "ccc", Some("Front".into()))?;
+        let out = col.find_and_replace(nids.clone(), "BBB", "ccc", Some("Front"))?;
         // 1, because notes without the specified field should be skipped
         assert_eq!(out.output, 1);
 
@@ -159,6 +189,43 @@ mod test {
         // the update should be limited to the specified field when it was available
         assert_eq!(&note.fields()[..], &["one ccc", "two BBB"]);
 
+        // backreferences work within a single field too
+        col.find_and_replace(nids, r"(\w+) (\w+)", "$2 $1", Some("Back"))?;
+        let note = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(&note.fields()[..], &["one ccc", "BBB two"]);
+        let note2 = col.storage.get_note(note2.id)?.unwrap();
+        assert_eq!(&note2.fields()[..], &["three BBB", ""]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn findreplace_backreferences() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut note = nt.new_note();
+        note.set_field(0, "12cm and 3cm")?;
+        col.add_note(&mut note, DeckId(1))?;
+        let nids = vec![note.id];
+
+        col.find_and_replace(nids.clone(), r"(\d+)cm", "${1} centimeters", None)?;
+        let note = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(note.fields()[0], "12 centimeters and 3 centimeters");
+
+        // unknown groups are rejected before anything is changed
+        assert!(col
+            .find_and_replace(nids.clone(), r"(\d+) ", "$2", None)
+            .is_err());
+        assert!(col
+            .find_and_replace(nids.clone(), r"(?P<num>\d+) ", "${number}", None)
+            .is_err());
+        assert_eq!(col.storage.get_note(note.id)?.unwrap(), note);
+
+        // named groups and escaped dollar signs are fine
+        col.find_and_replace(nids, r"(?P<num>\d+) ", "$$${num}", None)?;
+        let note = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(note.fields()[0], "$12centimeters and $3centimeters");
+
         Ok(())
     }
 }
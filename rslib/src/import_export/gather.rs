@@ -88,6 +88,16 @@ impl ExchangeData {
         Ok(())
     }
 
+    /// Drop the review history, flags and any buried or suspended state,
+    /// keeping the cards' intervals and ease.
+    pub(super) fn strip_review_history(&mut self) {
+        self.revlog.clear();
+        for card in self.cards.iter_mut() {
+            card.restore_queue_after_bury_or_suspend();
+            card.flags = 0;
+        }
+    }
+
     fn reset_cards_and_notes(&mut self, col: &Collection) {
         self.remove_system_tags();
         self.reset_cards(col);
@@ -14,6 +14,7 @@ use crate::collection::CollectionBuilder;
 use crate::import_export::gather::ExchangeData;
 use crate::import_export::package::colpkg::export::export_collection;
 use crate::import_export::package::media::MediaIter;
+use crate::import_export::package::AnonymizeScheduling;
 use crate::import_export::package::ExportAnkiPackageOptions;
 use crate::import_export::package::Meta;
 use crate::import_export::ExportProgress;
@@ -28,6 +29,24 @@ impl Collection {
         options: ExportAnkiPackageOptions,
         search: impl TryIntoSearch,
         media_fn: Option<Box<dyn FnOnce(HashSet<String>) -> MediaIter>>,
+    ) -> Result<usize> {
+        let meta = if options.legacy {
+            Meta::new_legacy()
+        } else {
+            Meta::new()
+        };
+        self.export_subset(out_path, meta, options, search, media_fn)
+    }
+
+    /// Write the notes matching the search, and anything they depend on, to
+    /// a package. Returns number of exported notes.
+    pub(crate) fn export_subset(
+        &mut self,
+        out_path: impl AsRef<Path>,
+        meta: Meta,
+        options: ExportAnkiPackageOptions,
+        search: impl TryIntoSearch,
+        media_fn: Option<Box<dyn FnOnce(HashSet<String>) -> MediaIter>>,
     ) -> Result<usize> {
         let mut progress = self.new_progress_handler();
         let temp_apkg = new_tempfile_in_parent_of(out_path.as_ref())?;
@@ -36,11 +55,6 @@ impl Collection {
             .path()
             .to_str()
             .or_invalid("non-unicode filename")?;
-        let meta = if options.legacy {
-            Meta::new_legacy()
-        } else {
-            Meta::new()
-        };
         let data =
             self.export_into_collection_file(&meta, temp_col_path, options, search, &mut progress)?;
 
@@ -75,12 +89,13 @@ impl Collection {
     ) -> Result<ExchangeData> {
         let mut data = ExchangeData::default();
         progress.set(ExportProgress::Gathering)?;
-        data.gather_data(
-            self,
-            search,
-            options.with_scheduling,
-            options.with_deck_configs,
-        )?;
+        let anonymize = options.anonymize_scheduling();
+        let with_scheduling =
+            options.with_scheduling && anonymize != AnonymizeScheduling::ResetToNew;
+        data.gather_data(self, search, with_scheduling, options.with_deck_configs)?;
+        if with_scheduling && anonymize == AnonymizeScheduling::KeepIntervalsOnly {
+            data.strip_review_history();
+        }
         if options.with_media {
             data.gather_media_names(progress)?;
         }
@@ -135,7 +135,8 @@ impl DeckContext<'_> {
         let mut deck = Deck::new_normal();
         deck.name = NativeDeckName::from_native_str(name);
         self.uniquify_name(&mut deck);
-        self.target_col.add_deck_inner(&mut deck, self.usn)
+        self.target_col.add_deck_inner(&mut deck, self.usn)?;
+        Ok(())
     }
 }
 
@@ -52,23 +52,32 @@ impl Context<'_> {
         )
     }
 
-    pub(super) fn copy_media(&mut self, media_map: &mut MediaUseMap) -> Result<()> {
+    /// Returns the names of files that failed verification, and were thus not
+    /// imported.
+    pub(super) fn copy_media(&mut self, media_map: &mut MediaUseMap) -> Result<Vec<String>> {
         let mut incrementor = self.progress.incrementor(ImportProgress::Media);
         let mut copier = MediaCopier::new(false);
+        let mut corrupt = Vec::new();
         self.media_manager.transact(|_db| {
             for entry in media_map.used_entries() {
                 incrementor.increment()?;
-                entry.copy_and_ensure_sha1_set(
+                if entry.copy_and_ensure_sha1_set(
                     &mut self.archive,
                     &self.target_col.media_folder,
                     &mut copier,
                     self.meta.zstd_compressed(),
-                )?;
-                self.media_manager
-                    .add_entry(&entry.name, entry.sha1.unwrap())?;
+                    self.verify_media,
+                )? {
+                    self.media_manager
+                        .add_entry(&entry.name, entry.sha1.unwrap())?;
+                } else {
+                    corrupt.push(entry.name.clone());
+                }
             }
             Ok(())
-        })
+        })?;
+        corrupt.sort_unstable();
+        Ok(corrupt)
     }
 }
 
@@ -40,6 +40,7 @@ struct Context<'a> {
     merge_notetypes: bool,
     update_notes: UpdateCondition,
     update_notetypes: UpdateCondition,
+    verify_media: bool,
     media_manager: MediaManager,
     archive: ZipArchive<File>,
     meta: Meta,
@@ -93,6 +94,7 @@ impl<'a> Context<'a> {
             merge_notetypes: options.merge_notetypes,
             update_notes: options.update_notes(),
             update_notetypes: options.update_notetypes(),
+            verify_media: options.verify_media,
             media_manager,
             archive,
             meta,
@@ -118,8 +120,9 @@ impl<'a> Context<'a> {
             &note_imports.remapped_templates,
             &imported_decks,
         )?;
-        self.copy_media(&mut media_map)?;
-        Ok(note_imports.log)
+        let mut log = note_imports.log;
+        log.corrupt_media = self.copy_media(&mut media_map)?;
+        Ok(log)
     }
 }
 
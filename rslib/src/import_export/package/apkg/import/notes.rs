@@ -630,6 +630,7 @@ mod test {
         remapped_notetypes: HashMap<NotetypeId, NotetypeId>,
         media_map: MediaUseMap,
         merge_notetypes: bool,
+        update_notes: UpdateCondition,
     }
 
     impl ImportBuilder {
@@ -657,6 +658,11 @@ mod test {
             self
         }
 
+        fn update_notes(mut self, condition: UpdateCondition) -> Self {
+            self.update_notes = condition;
+            self
+        }
+
         fn import(self, col: &mut Collection) -> NoteContext {
             let mut progress_handler = col.new_progress_handler();
             let media_map = Box::leak(Box::new(self.media_map));
@@ -665,7 +671,7 @@ mod test {
                 col,
                 media_map,
                 self.merge_notetypes,
-                UpdateCondition::IfNewer,
+                self.update_notes,
                 UpdateCondition::IfNewer,
             )
             .unwrap();
@@ -731,6 +737,21 @@ mod test {
         assert_eq!(col.get_all_notes()[0].fields()[0], "");
     }
 
+    #[test]
+    fn should_skip_newer_note_if_updates_are_disabled() {
+        let mut col = Collection::new();
+        let mut note = NoteAdder::basic(&mut col).add(&mut col);
+        note.mtime.0 += 1;
+        note.fields_mut()[0] = "updated".to_string();
+
+        let mut ctx = ImportBuilder::new()
+            .note(note)
+            .update_notes(UpdateCondition::Never)
+            .import(&mut col);
+        assert_note_logged!(ctx.imports.log, duplicate, &["updated", ""]);
+        assert_eq!(col.get_all_notes()[0].fields()[0], "");
+    }
+
     #[test]
     fn should_update_note_if_guid_already_exists_with_different_id() {
         let mut col = Collection::new();
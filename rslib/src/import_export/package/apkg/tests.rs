@@ -6,10 +6,17 @@
 use std::collections::HashSet;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 
 use anki_io::read_file;
 use anki_proto::import_export::ImportAnkiPackageOptions;
+use zip::write::FileOptions;
+use zip::ZipArchive;
+use zip::ZipWriter;
 
+use crate::card::CardQueue;
+use crate::card::CardType;
+use crate::import_export::package::AnonymizeScheduling;
 use crate::import_export::package::ExportAnkiPackageOptions;
 use crate::media::files::sha1_of_data;
 use crate::media::MediaManager;
@@ -50,6 +57,7 @@ fn roundtrip_inner(legacy: bool) {
                 with_deck_configs: true,
                 with_media: true,
                 legacy,
+                ..Default::default()
             },
             SearchNode::from_deck_name("parent::sample"),
             None,
@@ -67,6 +75,137 @@ fn roundtrip_inner(legacy: bool) {
     target_col.assert_empty();
 }
 
+#[test]
+fn corrupt_media_is_skipped_if_verifying() {
+    let (mut src_col, src_tempdir) = open_fs_test_collection("src");
+    let (mut target_col, _target_tempdir) = open_fs_test_collection("target");
+    let apkg_path = src_tempdir.path().join("test.apkg");
+    let corrupt_path = src_tempdir.path().join("corrupt.apkg");
+
+    let (main_deck, sibling_deck) = src_col.add_sample_decks();
+    let notetype = src_col.add_sample_notetype();
+    src_col.add_sample_note(&main_deck, &sibling_deck, &notetype);
+    src_col.add_sample_media();
+    src_col
+        .export_apkg(
+            &apkg_path,
+            ExportAnkiPackageOptions {
+                with_media: true,
+                ..Default::default()
+            },
+            "",
+            None,
+        )
+        .unwrap();
+    truncate_media_files(&apkg_path, &corrupt_path);
+
+    let log = target_col
+        .import_apkg(
+            &corrupt_path,
+            ImportAnkiPackageOptions {
+                verify_media: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .output;
+    assert_eq!(log.corrupt_media, [SAMPLE_JS, SAMPLE_JPG, SAMPLE_MP3]);
+    assert!(!target_col.media_folder.join(SAMPLE_JPG).exists());
+    // the notes are imported regardless
+    assert_eq!(log.new.len(), 1);
+
+    // without verification, the truncated files are imported as is
+    target_col.undo().unwrap();
+    let log = target_col
+        .import_apkg(&corrupt_path, ImportAnkiPackageOptions::default())
+        .unwrap()
+        .output;
+    assert!(log.corrupt_media.is_empty());
+    assert!(target_col.media_folder.join(SAMPLE_JPG).exists());
+}
+
+#[test]
+fn scheduling_can_be_anonymized() {
+    let (mut src_col, src_tempdir) = open_fs_test_collection("src");
+    let apkg_path = src_tempdir.path().join("test.apkg");
+    let mut note = src_col.basic_notetype().new_note();
+    src_col.add_note(&mut note, DeckId(1)).unwrap();
+    let cid = src_col.get_first_card().id;
+    src_col.set_due_date(&[cid], "5", None).unwrap();
+    src_col.suspend_cards(&[cid]).unwrap();
+    let original = src_col.get_first_card();
+
+    let mut export_and_import = |anonymize: AnonymizeScheduling| {
+        src_col
+            .export_apkg(
+                &apkg_path,
+                ExportAnkiPackageOptions {
+                    with_scheduling: true,
+                    anonymize_scheduling: anonymize as i32,
+                    ..Default::default()
+                },
+                "",
+                None,
+            )
+            .unwrap();
+        let (mut target_col, _target_tempdir) = open_fs_test_collection("target");
+        target_col
+            .import_apkg(
+                &apkg_path,
+                ImportAnkiPackageOptions {
+                    with_scheduling: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let revlog = target_col
+            .storage
+            .get_all_revlog_entries(TimestampSecs(0))
+            .unwrap();
+        (target_col.get_first_card(), revlog.len())
+    };
+
+    let (card, revlog) = export_and_import(AnonymizeScheduling::None);
+    assert_eq!(card.queue, CardQueue::Suspended);
+    assert_eq!(revlog, 1);
+
+    let (card, revlog) = export_and_import(AnonymizeScheduling::ResetToNew);
+    assert_eq!((card.ctype, card.queue), (CardType::New, CardQueue::New));
+    assert_eq!(revlog, 0);
+
+    let (card, revlog) = export_and_import(AnonymizeScheduling::KeepIntervalsOnly);
+    assert_eq!(
+        (card.ctype, card.queue),
+        (CardType::Review, CardQueue::Review)
+    );
+    assert_eq!(
+        (card.interval, card.ease_factor),
+        (original.interval, original.ease_factor)
+    );
+    assert_eq!(revlog, 0);
+
+    // the source collection is left alone
+    assert_eq!(src_col.get_first_card(), original);
+}
+
+/// Replace all media files in the package with empty ones.
+fn truncate_media_files(src: &Path, dst: &Path) {
+    let mut archive = ZipArchive::new(File::open(src).unwrap()).unwrap();
+    let mut zip = ZipWriter::new(File::create(dst).unwrap());
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).unwrap();
+        if file.name().parse::<usize>().is_ok() {
+            zip.start_file(file.name().to_string(), FileOptions::default())
+                .unwrap();
+            zip.write_all(&zstd::encode_all(&b""[..], 0).unwrap())
+                .unwrap();
+        } else {
+            zip.raw_copy_file(file).unwrap();
+        }
+    }
+    zip.finish().unwrap();
+}
+
 impl Collection {
     fn add_sample_decks(&mut self) -> (Deck, Deck) {
         let sample = self.add_named_deck("parent\x1fsample");
@@ -32,9 +32,11 @@ use crate::collection::CollectionBuilder;
 use crate::import_export::package::media::new_media_entry;
 use crate::import_export::package::media::MediaCopier;
 use crate::import_export::package::media::MediaIter;
+use crate::import_export::package::ExportAnkiPackageOptions;
 use crate::import_export::ExportProgress;
 use crate::prelude::*;
 use crate::progress::ThrottlingProgressHandler;
+use crate::search::SearchNode;
 use crate::storage::SchemaVersion;
 
 /// Enable multithreaded compression if over this size. For smaller files,
@@ -77,6 +79,33 @@ impl Collection {
 
         Ok(())
     }
+
+    /// Export a single deck, and optionally its subdecks, as a colpkg. Only
+    /// the notes of cards in those decks are included, along with the
+    /// notetypes, deck presets and media they use.
+    pub fn export_deck_colpkg(
+        &mut self,
+        did: DeckId,
+        include_subdecks: bool,
+        path: &Path,
+    ) -> Result<()> {
+        self.get_deck(did)?.or_not_found(did)?;
+        let search = if include_subdecks {
+            SearchNode::DeckIdWithChildren(did)
+        } else {
+            SearchNode::DeckIdsWithoutChildren(did.to_string())
+        };
+        let options = ExportAnkiPackageOptions {
+            with_scheduling: true,
+            with_deck_configs: true,
+            with_media: true,
+            legacy: false,
+            ..Default::default()
+        };
+        self.export_subset(path, Meta::new(), options, search, None)?;
+
+        Ok(())
+    }
 }
 
 fn export_collection_file(
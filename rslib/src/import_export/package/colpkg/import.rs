@@ -9,17 +9,22 @@ use std::path::PathBuf;
 
 use anki_io::atomic_rename;
 use anki_io::create_dir_all;
+use anki_io::new_tempfile;
 use anki_io::new_tempfile_in_parent_of;
 use anki_io::open_file;
 use anki_io::FileIoSnafu;
 use anki_io::FileOp;
+use rusqlite::Connection;
+use rusqlite::OpenFlags;
 use zip::read::ZipFile;
 use zip::ZipArchive;
 use zstd::stream::copy_decode;
+use zstd::stream::read::Decoder;
 
 use super::super::meta::MetaExt;
 use crate::collection::CollectionBuilder;
 use crate::import_export::package::media::extract_media_entries;
+use crate::import_export::package::media::MediaCopier;
 use crate::import_export::package::media::SafeMediaEntry;
 use crate::import_export::package::Meta;
 use crate::import_export::ImportError;
@@ -60,6 +65,94 @@ pub fn import_colpkg(
     Ok(())
 }
 
+/// Metadata of a colpkg file that passed [check_colpkg_integrity].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColpkgInfo {
+    pub schema_version: u8,
+    pub created: TimestampSecs,
+    pub note_count: u32,
+    pub card_count: u32,
+    pub media_count: usize,
+}
+
+/// Checks that the collection inside the colpkg passes SQLite's integrity
+/// check, and that the archived media files match the media list, without
+/// importing anything. Returns an error on the first problem found.
+pub fn check_colpkg_integrity(colpkg_path: &Path) -> Result<ColpkgInfo> {
+    let mut archive = ZipArchive::new(open_file(colpkg_path)?)?;
+    let meta = Meta::from_archive(&mut archive)?;
+
+    let mut tempfile = new_tempfile()?;
+    copy_collection(&mut archive, &mut tempfile, &meta)?;
+    let mut info = check_collection_and_get_info(tempfile.path())?;
+    info.media_count = check_media(&meta, &mut archive)?;
+
+    Ok(info)
+}
+
+fn check_collection_and_get_info(col_path: &Path) -> Result<ColpkgInfo> {
+    Connection::open_with_flags(col_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .ok()
+        .filter(|db| {
+            db.pragma_query_value(None, "integrity_check", |row| row.get::<_, String>(0))
+                .map_or(false, |s| s == "ok")
+        })
+        .and_then(|db| {
+            db.query_row(
+                "select ver, crt, (select count() from notes), (select count() from cards)
+                from col",
+                [],
+                |row| {
+                    Ok(ColpkgInfo {
+                        schema_version: row.get(0)?,
+                        created: TimestampSecs(row.get(1)?),
+                        note_count: row.get(2)?,
+                        card_count: row.get(3)?,
+                        media_count: 0,
+                    })
+                },
+            )
+            .ok()
+        })
+        .ok_or(AnkiError::ImportError {
+            source: ImportError::Corrupt,
+        })
+}
+
+/// Checks that every file in the media list is archived, with the recorded
+/// size and hash if present, and that no unlisted files are archived.
+/// Returns the number of media files.
+fn check_media(meta: &Meta, archive: &mut ZipArchive<File>) -> Result<usize> {
+    let media_entries = extract_media_entries(meta, archive)?;
+    let mut copier = MediaCopier::new(false);
+    for entry in &media_entries {
+        let media_failed = || AnkiError::ImportError {
+            source: ImportError::MediaImportFailed {
+                info: entry.name.clone(),
+            },
+        };
+        let file = entry.fetch_file(archive).map_err(|_| media_failed())?;
+        if meta.zstd_compressed() {
+            let (size, sha1) = copier.copy(&mut Decoder::new(file)?, &mut io::sink())?;
+            if size != entry.size as usize || Some(sha1) != entry.sha1 {
+                return Err(media_failed());
+            }
+        }
+    }
+
+    let archived_media = archive
+        .file_names()
+        .filter(|name| name.parse::<usize>().is_ok())
+        .count();
+    if archived_media != media_entries.len() {
+        return Err(AnkiError::ImportError {
+            source: ImportError::Corrupt,
+        });
+    }
+
+    Ok(media_entries.len())
+}
+
 fn check_collection_and_mod_schema(col_path: &Path) -> Result<()> {
     CollectionBuilder::new(col_path)
         .build()
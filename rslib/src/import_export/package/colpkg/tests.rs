@@ -6,11 +6,17 @@
 use std::path::Path;
 
 use anki_io::create_dir_all;
+use anki_io::create_file;
+use anki_io::open_file;
 use anki_io::read_file;
 use tempfile::tempdir;
+use zip::ZipArchive;
+use zip::ZipWriter;
 
 use crate::collection::CollectionBuilder;
+use crate::import_export::package::check_colpkg_integrity;
 use crate::import_export::package::import_colpkg;
+use crate::import_export::ImportError;
 use crate::media::MediaManager;
 use crate::prelude::*;
 
@@ -98,3 +104,99 @@ fn normalization_check_on_export() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn deck_subtree_roundtrip() -> Result<()> {
+    let _dir = tempdir()?;
+    let dir = _dir.path();
+    let mut col = CollectionBuilder::new(dir.join("subtree_src.anki2"))
+        .with_desktop_media_paths()
+        .build()?;
+    let parent = col.get_or_create_normal_deck("parent")?;
+    let child = col.get_or_create_normal_deck("parent::child")?;
+    let other = col.get_or_create_normal_deck("other")?;
+    for (notetype, deck, media) in [
+        ("Basic", parent.id, "a.jpg"),
+        ("Basic", child.id, "b.jpg"),
+        ("Cloze", other.id, "c.jpg"),
+    ] {
+        let nt = col.get_notetype_by_name(notetype)?.unwrap();
+        let mut note = nt.new_note();
+        note.set_field(0, format!("{{{{c1::text}}}}<img src='{media}'>"))?;
+        col.add_note(&mut note, deck)?;
+        col.media()?.add_file(media, media.as_bytes())?;
+    }
+
+    for (include_subdecks, notes, media) in [
+        (true, 2, &["a.jpg", "b.jpg"][..]),
+        (false, 1, &["a.jpg"][..]),
+    ] {
+        let name = format!("subtree{include_subdecks}");
+        let colpkg_name = dir.join(format!("{name}.colpkg"));
+        col.export_deck_colpkg(parent.id, include_subdecks, &colpkg_name)?;
+
+        let anki2_name = dir
+            .join(format!("{name}.anki2"))
+            .to_string_lossy()
+            .into_owned();
+        let import_media_dir = dir.join(format!("{name}.media"));
+        create_dir_all(&import_media_dir)?;
+        let import_media_db = dir.join(format!("{name}.mdb"));
+        MediaManager::new(&import_media_dir, &import_media_db)?;
+        import_colpkg(
+            &colpkg_name.to_string_lossy(),
+            &anki2_name,
+            &import_media_dir,
+            &import_media_db,
+            col.new_progress_handler(),
+        )?;
+
+        let imported = CollectionBuilder::new(&anki2_name).build()?;
+        assert_eq!(imported.storage.get_all_note_ids()?.len(), notes);
+        // the unused cloze notetype should have been left out
+        assert_eq!(imported.storage.get_all_notetype_names()?.len(), 1);
+        assert!(imported.get_deck_id("other")?.is_none());
+        for file in ["a.jpg", "b.jpg", "c.jpg"] {
+            assert_eq!(import_media_dir.join(file).exists(), media.contains(&file));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn integrity_check() -> Result<()> {
+    let _dir = tempdir()?;
+    let dir = _dir.path();
+
+    for (legacy, name, schema_version) in [(true, "check_legacy", 11), (false, "check_v3", 18)] {
+        let col = collection_with_media(dir, name)?;
+        let colpkg_name = dir.join(format!("{name}.colpkg"));
+        col.export_colpkg(&colpkg_name, true, legacy)?;
+
+        let info = check_colpkg_integrity(&colpkg_name)?;
+        assert_eq!(info.schema_version, schema_version);
+        assert_eq!((info.note_count, info.card_count), (1, 1));
+        assert_eq!(info.media_count, 3);
+    }
+
+    // drop one of the media files from the archive
+    let mut archive = ZipArchive::new(open_file(dir.join("check_v3.colpkg"))?)?;
+    let broken_name = dir.join("broken.colpkg");
+    let mut writer = ZipWriter::new(create_file(&broken_name)?);
+    for idx in 0..archive.len() {
+        let file = archive.by_index_raw(idx)?;
+        if file.name() != "1" {
+            writer.raw_copy_file(file)?;
+        }
+    }
+    writer.finish()?;
+    assert!(matches!(
+        check_colpkg_integrity(&broken_name),
+        Err(AnkiError::ImportError {
+            source: ImportError::MediaImportFailed { .. }
+        })
+    ));
+
+    Ok(())
+}
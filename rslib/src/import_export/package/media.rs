@@ -26,6 +26,7 @@ use zip::result::ZipError;
 use zip::ZipArchive;
 use zstd::stream::copy_decode;
 use zstd::stream::raw::Encoder as RawEncoder;
+use zstd::stream::read::Decoder;
 
 use super::meta::MetaExt;
 use super::MediaEntries;
@@ -117,24 +118,35 @@ impl SafeMediaEntry {
 
     /// Copy the archived file to the target folder, setting its hash if
     /// necessary.
+    /// If `verify` is set, the extracted data is checked against the size and
+    /// hash recorded in the media list, and the file is not written if they
+    /// don't match. Returns false in that case.
     pub(super) fn copy_and_ensure_sha1_set(
         &mut self,
         archive: &mut ZipArchive<File>,
         target_folder: &Path,
         copier: &mut MediaCopier,
         compressed: bool,
-    ) -> Result<()> {
+        verify: bool,
+    ) -> Result<bool> {
         let mut file = self.fetch_file(archive)?;
         let mut tempfile = new_tempfile_in(target_folder)?;
-        if compressed {
+        if compressed && verify {
+            let (size, sha1) = copier.copy(&mut Decoder::new(file)?, &mut tempfile)?;
+            if size != self.size as usize || Some(sha1) != self.sha1 {
+                return Ok(false);
+            }
+        } else if compressed {
             copy_decode(&mut file, &mut tempfile)?
         } else {
+            // legacy media lists record neither size nor hash, so there is
+            // nothing to verify against
             let (_, sha1) = copier.copy(&mut file, &mut tempfile)?;
             self.sha1 = Some(sha1);
         }
         atomic_rename(tempfile, &self.file_path(target_folder), false)?;
 
-        Ok(())
+        Ok(true)
     }
 }
 
@@ -7,13 +7,16 @@ mod media;
 mod meta;
 
 use anki_proto::import_export::media_entries::MediaEntry;
+pub use anki_proto::import_export::AnonymizeScheduling;
 pub use anki_proto::import_export::ExportAnkiPackageOptions;
 pub use anki_proto::import_export::ImportAnkiPackageOptions;
 pub use anki_proto::import_export::ImportAnkiPackageUpdateCondition as UpdateCondition;
 use anki_proto::import_export::MediaEntries;
 pub(crate) use apkg::NoteMeta;
 pub(crate) use colpkg::export::export_colpkg_from_data;
+pub use colpkg::import::check_colpkg_integrity;
 pub use colpkg::import::import_colpkg;
+pub use colpkg::import::ColpkgInfo;
 pub use media::MediaIter;
 pub use media::MediaIterEntry;
 pub use media::MediaIterError;
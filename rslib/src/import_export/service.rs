@@ -25,6 +25,7 @@ impl crate::services::ImportExportService for Collection {
             with_deck_configs: self.get_config_bool(BoolKey::WithDeckConfigs),
             update_notes: self.get_update_notes() as i32,
             update_notetypes: self.get_update_notetypes() as i32,
+            verify_media: false,
         })
     }
 
@@ -71,6 +72,13 @@ impl crate::services::ImportExportService for Collection {
         self.export_note_csv(input).map(Into::into)
     }
 
+    fn export_note_markdown(
+        &mut self,
+        input: anki_proto::import_export::ExportNoteMarkdownRequest,
+    ) -> Result<generic::UInt32> {
+        self.export_note_markdown(input).map(Into::into)
+    }
+
     fn export_card_csv(
         &mut self,
         input: anki_proto::import_export::ExportCardCsvRequest,
@@ -5,9 +5,11 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 use std::sync::Arc;
 
 use anki_proto::import_export::ExportNoteCsvRequest;
+use chrono::FixedOffset;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -17,6 +19,8 @@ use crate::import_export::text::csv::metadata::DelimeterExt;
 use crate::import_export::ExportProgress;
 use crate::notetype::RenderCardOutput;
 use crate::prelude::*;
+use crate::revlog::RevlogEntry;
+use crate::revlog::RevlogReviewKind;
 use crate::search::SearchNode;
 use crate::search::SortMode;
 use crate::template::RenderedNode;
@@ -68,6 +72,35 @@ impl Collection {
         Ok(incrementor.count())
     }
 
+    /// Write the review history of the cards matching `search` to a
+    /// comma-separated file, one row per review, and return the number of
+    /// rows written. Review times are given in the user's timezone, and the
+    /// review day takes the day rollover hour into account.
+    pub fn export_revlog_csv(&mut self, search: impl TryIntoSearch, path: &Path) -> Result<usize> {
+        let utc_offset = self.local_utc_offset_for_user()?;
+        let rollover = self.rollover_for_current_scheduler()?;
+        let mut writer = csv::Writer::from_writer(File::create(path)?);
+        writer
+            .write_record(REVLOG_COLUMNS)
+            .or_invalid("invalid csv")?;
+
+        let mut rows = 0;
+        let guard = self.search_cards_into_table(search, SortMode::NoOrder)?;
+        guard
+            .col
+            .storage
+            .for_each_revlog_entry_of_searched_cards(|entry, nid| {
+                writer
+                    .write_record(revlog_record(&entry, nid, utc_offset, rollover)?)
+                    .or_invalid("invalid csv")?;
+                rows += 1;
+                Ok(())
+            })?;
+        writer.flush()?;
+
+        Ok(rows)
+    }
+
     fn card_record(&mut self, card: CardId, with_html: bool) -> Result<[String; 2]> {
         let RenderCardOutput { qnodes, anodes, .. } =
             self.render_existing_card(card, false, false)?;
@@ -78,6 +111,53 @@ impl Collection {
     }
 }
 
+const REVLOG_COLUMNS: [&str; 10] = [
+    "card_id",
+    "note_id",
+    "review_time",
+    "review_day",
+    "button",
+    "interval",
+    "last_interval",
+    "ease",
+    "taken_millis",
+    "review_kind",
+];
+
+fn revlog_record(
+    entry: &RevlogEntry,
+    nid: NoteId,
+    utc_offset: FixedOffset,
+    rollover_hour: u8,
+) -> Result<[String; 10]> {
+    let stamp = entry.id.as_secs();
+    let review_time = stamp.datetime(utc_offset)?.to_rfc3339();
+    let review_day = stamp
+        .adding_secs(-(rollover_hour as i64) * 3600)
+        .datetime(utc_offset)?
+        .format("%Y-%m-%d")
+        .to_string();
+    let review_kind = match entry.review_kind {
+        RevlogReviewKind::Learning => "learning",
+        RevlogReviewKind::Review => "review",
+        RevlogReviewKind::Relearning => "relearning",
+        RevlogReviewKind::Filtered => "filtered",
+        RevlogReviewKind::Manual => "manual",
+    };
+    Ok([
+        entry.cid.to_string(),
+        nid.to_string(),
+        review_time,
+        review_day,
+        entry.button_chosen.to_string(),
+        entry.interval.to_string(),
+        entry.last_interval.to_string(),
+        entry.ease_factor.to_string(),
+        entry.taken_millis.to_string(),
+        review_kind.to_string(),
+    ])
+}
+
 fn file_writer_with_header(path: &str, with_html: bool) -> Result<csv::Writer<File>> {
     let mut file = File::create(path)?;
     write_file_header(&mut file, with_html)?;
@@ -282,3 +362,30 @@ impl From<&mut ExportNoteCsvRequest> for SearchNode {
         SearchNode::from(req.limit.take().unwrap_or_default())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::NoteAdder;
+
+    #[test]
+    fn revlog_export() -> Result<()> {
+        let mut col = Collection::new();
+        let note = NoteAdder::basic(&mut col).add(&mut col);
+        NoteAdder::basic(&mut col).add(&mut col);
+        col.answer_good();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("revlog.csv");
+        assert_eq!(col.export_revlog_csv("", &path)?, 1);
+        let csv = std::fs::read_to_string(&path)?;
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], REVLOG_COLUMNS.join(","));
+        assert_eq!(lines.len(), 2);
+        let cid = col.storage.card_ids_of_notes(&[note.id])?[0];
+        assert!(lines[1].starts_with(&format!("{cid},{}", note.id)));
+        assert!(lines[1].ends_with(",learning"));
+
+        Ok(())
+    }
+}
@@ -225,6 +225,7 @@ fn remove_tags_line_from_reader(reader: &mut (impl Read + Seek)) -> Result<()> {
 mod test {
     use std::io::Cursor;
 
+    use anki_io::write_file;
     use anki_proto::import_export::csv_metadata::MappedNotetype;
 
     use super::super::metadata::test::CsvMetadataTestExt;
@@ -328,6 +329,35 @@ mod test {
         assert_eq!(notes[0].tags.as_ref().unwrap(), &["foo", "bar"]);
     }
 
+    #[test]
+    fn should_merge_tag_column_with_global_tags() -> Result<()> {
+        let mut col = Collection::new();
+        let ntid = col.get_notetype_by_name("Basic")?.unwrap().id;
+        let mut metadata = CsvMetadata::defaults_for_testing();
+        metadata
+            .notetype
+            .replace(CsvNotetype::GlobalNotetype(MappedNotetype {
+                id: ntid.0,
+                field_columns: vec![1, 2],
+            }));
+        metadata.tags_column = 3;
+        metadata.column_labels.push("".to_string());
+        metadata.global_tags = vec!["global".to_string()];
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("notes.csv");
+        write_file(&path, "one,1,foo  bar foo global\ntwo,2,\n")?;
+
+        col.import_csv(path.to_str().unwrap(), metadata)?;
+        let tags: Vec<_> = col
+            .storage
+            .get_all_notes()
+            .into_iter()
+            .map(|note| note.tags)
+            .collect();
+        assert_eq!(tags, [vec!["bar", "foo", "global"], vec!["global"]]);
+        Ok(())
+    }
+
     #[test]
     fn should_parse_deck_column() {
         let mut metadata = CsvMetadata::defaults_for_testing();
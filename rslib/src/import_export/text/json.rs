@@ -1,11 +1,20 @@
 // Copyright: Ankitects Pty Ltd and contributors
 // License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
 
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
 use anki_io::read_file;
+use serde::Serialize;
+use serde::Serializer;
 
 use crate::import_export::text::ForeignData;
 use crate::import_export::NoteLog;
 use crate::prelude::*;
+use crate::text::html_to_text_line;
 
 impl Collection {
     pub fn import_json_file(&mut self, path: &str) -> Result<OpOutput<NoteLog>> {
@@ -20,4 +29,118 @@ impl Collection {
         let data: ForeignData = serde_json::from_str(json)?;
         data.import(self, progress)
     }
+
+    /// Write the notes matching `search` to a JSON Lines file, one object per
+    /// note, and return the number of notes written. Fields keep their HTML
+    /// unless `strip_html` is true.
+    pub fn export_notes_jsonl(
+        &mut self,
+        search: &str,
+        path: &Path,
+        strip_html: bool,
+    ) -> Result<usize> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let mut count = 0;
+        let guard = self.search_notes_into_table(search)?;
+        let notetypes = guard.col.get_all_notetypes_of_search_notes()?;
+        guard.col.storage.for_each_note_in_search(|note| {
+            let notetype = notetypes
+                .get(&note.notetype_id)
+                .or_not_found(note.notetype_id)?;
+            serde_json::to_writer(
+                &mut writer,
+                &JsonLinesNote::new(&note, notetype, strip_html),
+            )?;
+            writer.write_all(b"\n")?;
+            count += 1;
+            Ok(())
+        })?;
+        writer.flush()?;
+
+        Ok(count)
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLinesNote<'a> {
+    id: NoteId,
+    guid: &'a str,
+    notetype: &'a str,
+    tags: &'a [String],
+    #[serde(serialize_with = "serialize_in_order")]
+    fields: Vec<(&'a str, Cow<'a, str>)>,
+}
+
+impl<'a> JsonLinesNote<'a> {
+    fn new(note: &'a Note, notetype: &'a Notetype, strip_html: bool) -> Self {
+        Self {
+            id: note.id,
+            guid: &note.guid,
+            notetype: &notetype.name,
+            tags: &note.tags,
+            fields: notetype
+                .fields
+                .iter()
+                .zip(note.fields())
+                .map(|(field, text)| {
+                    let text = if strip_html {
+                        html_to_text_line(text, false)
+                    } else {
+                        text.into()
+                    };
+                    (field.name.as_str(), text)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Serializes the fields as a map, keeping the notetype's field order.
+fn serialize_in_order<S: Serializer>(
+    fields: &[(&str, Cow<str>)],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(fields.iter().map(|(name, text)| (name, text)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::NoteAdder;
+
+    #[test]
+    fn notes_jsonl_export() -> Result<()> {
+        let mut col = Collection::new();
+        let mut note = NoteAdder::basic(&mut col)
+            .fields(&["<b>front</b>", "back"])
+            .add(&mut col);
+        note.tags = vec!["one".to_string(), "two".to_string()];
+        col.update_note(&mut note)?;
+        NoteAdder::basic(&mut col)
+            .fields(&["other", ""])
+            .add(&mut col);
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("notes.jsonl");
+        assert_eq!(col.export_notes_jsonl("front", &path, false)?, 1);
+        let json: serde_json::Value =
+            serde_json::from_str(std::fs::read_to_string(&path)?.trim_end())?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "id": note.id,
+                "guid": note.guid,
+                "notetype": "Basic",
+                "tags": ["one", "two"],
+                "fields": {"Front": "<b>front</b>", "Back": "back"},
+            })
+        );
+
+        assert_eq!(col.export_notes_jsonl("", &path, true)?, 2);
+        let json = std::fs::read_to_string(&path)?;
+        assert_eq!(json.lines().count(), 2);
+        assert!(json.contains(r#""Front":"front""#));
+
+        Ok(())
+    }
 }
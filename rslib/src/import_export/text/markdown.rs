@@ -0,0 +1,135 @@
+// Copyright: Ankitects Pty Ltd and contributors
+// License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+
+use anki_proto::import_export::ExportNoteMarkdownRequest;
+use lazy_static::lazy_static;
+use regex::Captures;
+use regex::Regex;
+
+use crate::import_export::ExportProgress;
+use crate::notetype::Notetype;
+use crate::prelude::*;
+use crate::search::SearchNode;
+use crate::text::strip_html;
+use crate::text::CowMapping;
+use crate::text::Trimming;
+use crate::text::SOUND_TAG;
+
+lazy_static! {
+    static ref IMAGE: Regex =
+        Regex::new(r#"(?is)<img\b[^>]*?\bsrc\s*=\s*["']([^"']*)["'][^>]*>"#).unwrap();
+    static ref LINK: Regex =
+        Regex::new(r#"(?is)<a\b[^>]*?\bhref\s*=\s*["']([^"']*)["'][^>]*>(.*?)</a>"#).unwrap();
+    static ref BOLD: Regex = Regex::new(r"(?is)<(?:b|strong)\b[^>]*>(.*?)</(?:b|strong)>").unwrap();
+    static ref ITALIC: Regex = Regex::new(r"(?is)<(?:i|em)\b[^>]*>(.*?)</(?:i|em)>").unwrap();
+    static ref LINEBREAK: Regex = Regex::new(r"(?i)<br\s*/?>|</?(?:div|p)\b[^>]*>").unwrap();
+    static ref EXCESS_NEWLINES: Regex = Regex::new(r"\n{3,}").unwrap();
+}
+
+impl Collection {
+    /// Write the limited notes to a Markdown file, with a heading for each
+    /// non-empty field. Returns the number of exported notes.
+    pub fn export_note_markdown(
+        &mut self,
+        mut request: ExportNoteMarkdownRequest,
+    ) -> Result<usize> {
+        let mut progress = self.new_progress_handler::<ExportProgress>();
+        let mut incrementor = progress.incrementor(ExportProgress::Notes);
+
+        let guard = self
+            .search_notes_into_table(SearchNode::from(request.limit.take().unwrap_or_default()))?;
+        let notetypes = guard.col.get_all_notetypes_of_search_notes()?;
+        let mut writer = BufWriter::new(File::create(&request.out_path)?);
+        guard.col.storage.for_each_note_in_search(|note| {
+            incrementor.increment()?;
+            let notetype = notetypes
+                .get(&note.notetype_id)
+                .or_not_found(note.notetype_id)?;
+            write_note(&mut writer, &note, notetype)
+        })?;
+        writer.flush()?;
+
+        Ok(incrementor.count())
+    }
+}
+
+fn write_note(writer: &mut impl Write, note: &Note, notetype: &Notetype) -> Result<()> {
+    for (field, config) in note.fields().iter().zip(&notetype.fields) {
+        let text = html_to_markdown(field);
+        if !text.is_empty() {
+            writeln!(writer, "## {}\n\n{text}\n", config.name)?;
+        }
+    }
+    writeln!(writer, "---\n")?;
+    Ok(())
+}
+
+/// Best-effort conversion of field HTML to Markdown. Bold, italics, links,
+/// images and line breaks are converted, and other tags are stripped. Media
+/// references become links relative to the media folder. Cloze deletions are
+/// left as they are.
+fn html_to_markdown(html: &str) -> Cow<str> {
+    IMAGE
+        .replace_all(html, |caps: &Captures| {
+            format!("![]({})", link_target(&caps[1]))
+        })
+        .map_cow(|s| {
+            LINK.replace_all(s, |caps: &Captures| {
+                format!("[{}]({})", &caps[2], link_target(&caps[1]))
+            })
+        })
+        .map_cow(|s| {
+            SOUND_TAG.replace_all(s, |caps: &Captures| {
+                format!("[{}]({})", &caps[1], link_target(&caps[1]))
+            })
+        })
+        .map_cow(|s| BOLD.replace_all(s, "**$1**"))
+        .map_cow(|s| ITALIC.replace_all(s, "*$1*"))
+        .map_cow(|s| LINEBREAK.replace_all(s, "\n"))
+        .map_cow(strip_html)
+        .map_cow(|s| EXCESS_NEWLINES.replace_all(s, "\n\n"))
+        .trim()
+}
+
+/// Percent-encode characters that would end a Markdown link target early.
+fn link_target(target: &str) -> String {
+    target
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn html_conversion() {
+        assert_eq!(
+            html_to_markdown("<b>bold</b> and <i>it</i>"),
+            "**bold** and *it*"
+        );
+        assert_eq!(
+            html_to_markdown("one<br>two<div>three</div>"),
+            "one\ntwo\nthree"
+        );
+        assert_eq!(
+            html_to_markdown(r#"<a href="https://x.org">x</a> <span>&amp;</span>"#),
+            "[x](https://x.org) &"
+        );
+        assert_eq!(
+            html_to_markdown(r#"<img src="my pic.jpg"> [sound:a.mp3]"#),
+            "![](my%20pic.jpg) [a.mp3](a.mp3)"
+        );
+        assert_eq!(
+            html_to_markdown("{{c1::<b>cloze</b>::hint}}"),
+            "{{c1::**cloze**::hint}}"
+        );
+        assert_eq!(html_to_markdown("<div></div><div><br></div>"), "");
+    }
+}
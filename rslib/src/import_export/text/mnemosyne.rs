@@ -0,0 +1,374 @@
+// Copyright: Ankitects Pty Ltd and contributors
+// License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
+
+//! Importing of Mnemosyne 2.x databases.
+//!
+//! The mapping is lossy:
+//! - Only the built-in front-only, front-to-back, vocabulary and cloze card
+//!   types are understood; facts of other card types (sentences, maps, plugin
+//!   types) are skipped.
+//! - Mnemosyne's grades are dropped. Cards that have been memorised become
+//!   review cards with the interval inferred from their last and next
+//!   repetition; unmemorised cards are imported as new.
+//! - A card can only keep its scheduling if all cards with lower ordinals of
+//!   the same fact have been memorised too; the others are imported as new.
+//! - Tags are kept, and the first one (if any) is used as the deck.
+//! - Card-level data like `extra_data` and the review log is not imported.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::Connection;
+use rusqlite::OpenFlags;
+
+use crate::import_export::text::ForeignCard;
+use crate::import_export::text::ForeignData;
+use crate::import_export::text::ForeignNote;
+use crate::import_export::text::ForeignNotetype;
+use crate::import_export::text::ForeignTemplate;
+use crate::import_export::text::NameOrId;
+use crate::import_export::NoteLog;
+use crate::prelude::*;
+use crate::text::CowMapping;
+
+/// The tag Mnemosyne assigns to cards without any tags.
+const UNTAGGED: &str = "__UNTAGGED__";
+
+impl Collection {
+    pub fn import_mnemosyne(&mut self, path: &Path) -> Result<OpOutput<NoteLog>> {
+        let progress = self.new_progress_handler();
+        let db = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let facts = gather_facts(&db)?;
+        let mut data = ForeignData::from_mnemosyne_facts(facts);
+        data.notetypes.retain(|nt| {
+            self.get_notetype_by_name(&nt.name)
+                .map_or(true, |existing| existing.is_none())
+        });
+        data.import(self, progress)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum FactKind {
+    FrontOnly,
+    FrontBack,
+    Vocabulary,
+    Cloze,
+}
+
+#[derive(Debug, Default)]
+struct MnemosyneFact {
+    kind: Option<FactKind>,
+    data: HashMap<String, String>,
+    tags: Vec<String>,
+    cards: Vec<Option<ForeignCard>>,
+}
+
+fn gather_facts(db: &Connection) -> Result<Vec<MnemosyneFact>> {
+    let mut facts: HashMap<i64, MnemosyneFact> = HashMap::new();
+    let mut stmt = db.prepare("select _fact_id, key, value from data_for_fact")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        facts
+            .entry(row.get(0)?)
+            .or_default()
+            .data
+            .insert(row.get(1)?, row.get(2)?);
+    }
+
+    let mut stmt = db.prepare(
+        "select _fact_id, fact_view_id, tags, next_rep, last_rep, easiness,
+        acq_reps + ret_reps, lapses from cards order by _fact_id, fact_view_id",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let Some(fact) = facts.get_mut(&row.get::<_, i64>(0)?) else {
+            continue;
+        };
+        let Some((kind, ord)) = parse_fact_view(&row.get::<_, String>(1)?) else {
+            continue;
+        };
+        fact.kind.get_or_insert(kind);
+        fact.add_tags(&row.get::<_, Option<String>>(2)?.unwrap_or_default());
+        let next_rep: i64 = row.get(3)?;
+        if next_rep == -1 {
+            // not memorised yet
+            continue;
+        }
+        let last_rep: i64 = row.get(4)?;
+        let card = ForeignCard {
+            due: next_rep as i32,
+            interval: ((next_rep - last_rep) / 86_400).max(1) as u32,
+            ease_factor: row.get::<_, f64>(5)? as f32,
+            reps: row.get(6)?,
+            lapses: row.get(7)?,
+        };
+        if fact.cards.len() <= ord {
+            fact.cards.resize(ord + 1, None);
+        }
+        fact.cards[ord] = Some(card);
+    }
+
+    let mut facts: Vec<_> = facts.into_iter().collect();
+    facts.sort_unstable_by_key(|(id, _)| *id);
+    Ok(facts.into_iter().map(|(_, fact)| fact).collect())
+}
+
+/// Returns the kind of fact and the card ordinal for a `fact_view_id` like
+/// `2.1` or `1::custom.1`.
+fn parse_fact_view(fact_view_id: &str) -> Option<(FactKind, usize)> {
+    let (card_type, view) = fact_view_id.rsplit_once('.')?;
+    let ord = view.parse::<usize>().ok()?.checked_sub(1)?;
+    let kind = match card_type.split("::").next()? {
+        "1" => FactKind::FrontOnly,
+        "2" => FactKind::FrontBack,
+        "3" => FactKind::Vocabulary,
+        "5" => FactKind::Cloze,
+        _ => return None,
+    };
+    Some((kind, ord))
+}
+
+impl MnemosyneFact {
+    fn add_tags(&mut self, tags: &str) {
+        for tag in tags.split(',').map(str::trim) {
+            if tag.is_empty() || tag == UNTAGGED {
+                continue;
+            }
+            let tag = tag.replace(' ', "_");
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+    }
+
+    fn field(&self, key: &str) -> Option<String> {
+        Some(munge_field(self.data.get(key).map_or("", String::as_str)).into_owned())
+    }
+
+    fn into_foreign_note(self, kind: FactKind) -> ForeignNote {
+        let fields = match kind {
+            FactKind::FrontOnly | FactKind::FrontBack => {
+                vec![self.field("f"), self.field("b")]
+            }
+            FactKind::Vocabulary => vec![
+                self.field("f"),
+                self.field("p_1"),
+                self.field("m_1"),
+                self.field("n"),
+            ],
+            FactKind::Cloze => vec![
+                Some(clozes_from_brackets(
+                    self.data.get("text").map_or("", String::as_str),
+                )),
+                Some(String::new()),
+            ],
+        };
+        let deck = self
+            .tags
+            .first()
+            .map(|tag| NameOrId::Name(tag.clone()))
+            .unwrap_or_default();
+        ForeignNote {
+            fields,
+            notetype: NameOrId::Name(kind.notetype_name().to_string()),
+            deck,
+            cards: self.cards.into_iter().map_while(|card| card).collect(),
+            tags: Some(self.tags),
+            ..Default::default()
+        }
+    }
+}
+
+/// Converts Mnemosyne's newlines, LaTeX and audio markup.
+fn munge_field(text: &str) -> Cow<str> {
+    static NEWLINE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\r?\n").unwrap());
+    static LATEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<(/?(\$|\$\$|latex))>").unwrap());
+    static AUDIO: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"<audio src="(.+?)">(</audio>)?"#).unwrap());
+
+    NEWLINE
+        .replace_all(text, "<br>")
+        .map_cow(|s| LATEX.replace_all(s, "[$1]"))
+        .map_cow(|s| AUDIO.replace_all(s, "[sound:$1]"))
+}
+
+/// Turns Mnemosyne's `[hidden]` cloze syntax into numbered cloze deletions.
+fn clozes_from_brackets(text: &str) -> String {
+    static BRACKETS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[(.+?)\]").unwrap());
+    let mut ord = 0;
+    let text = BRACKETS.replace_all(text, |caps: &regex::Captures| {
+        ord += 1;
+        format!("{{{{c{}::{}}}}}", ord, &caps[1])
+    });
+    munge_field(&text).into_owned()
+}
+
+impl FactKind {
+    fn notetype_name(self) -> &'static str {
+        match self {
+            FactKind::FrontOnly => "Mnemosyne-FrontOnly",
+            FactKind::FrontBack => "Mnemosyne-FrontBack",
+            FactKind::Vocabulary => "Mnemosyne-Vocabulary",
+            FactKind::Cloze => "Mnemosyne-Cloze",
+        }
+    }
+
+    fn foreign_notetype(self) -> ForeignNotetype {
+        let template = |name: &str, qfmt: &str, afmt: &str| ForeignTemplate {
+            name: name.to_string(),
+            qfmt: qfmt.to_string(),
+            afmt: format!("{qfmt}\n\n<hr id=answer>\n\n{afmt}"),
+        };
+        let (fields, templates): (&[&str], _) = match self {
+            FactKind::FrontOnly => (
+                &["Front", "Back"],
+                vec![template("Card 1", "{{Front}}", "{{Back}}")],
+            ),
+            FactKind::FrontBack => (
+                &["Front", "Back"],
+                vec![
+                    template("Card 1", "{{Front}}", "{{Back}}"),
+                    template("Back", "{{Back}}", "{{Front}}"),
+                ],
+            ),
+            FactKind::Vocabulary => (
+                &["Expression", "Pronunciation", "Meaning", "Notes"],
+                vec![
+                    template(
+                        "Recognition",
+                        "{{Expression}}",
+                        "{{Pronunciation}}<br>\n{{Meaning}}<br>\n{{Notes}}",
+                    ),
+                    template(
+                        "Production",
+                        "{{Meaning}}",
+                        "{{Expression}}<br>\n{{Pronunciation}}<br>\n{{Notes}}",
+                    ),
+                ],
+            ),
+            FactKind::Cloze => (
+                &["Text", "Back Extra"],
+                vec![ForeignTemplate {
+                    name: "Cloze".to_string(),
+                    qfmt: "{{cloze:Text}}".to_string(),
+                    afmt: "{{cloze:Text}}<br>\n{{Back Extra}}".to_string(),
+                }],
+            ),
+        };
+        ForeignNotetype {
+            name: self.notetype_name().to_string(),
+            fields: fields.iter().map(ToString::to_string).collect(),
+            templates,
+            is_cloze: self == FactKind::Cloze,
+        }
+    }
+}
+
+impl ForeignData {
+    fn from_mnemosyne_facts(facts: Vec<MnemosyneFact>) -> Self {
+        let mut kinds = Vec::new();
+        let notes = facts
+            .into_iter()
+            .filter_map(|fact| {
+                let kind = fact.kind?;
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+                Some(fact.into_foreign_note(kind))
+            })
+            .collect();
+        kinds.sort_unstable();
+        Self {
+            default_deck: NameOrId::Id(1),
+            notes,
+            notetypes: kinds.into_iter().map(FactKind::foreign_notetype).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::search::SearchNode;
+    use crate::search::SortMode;
+
+    fn write_mnemosyne_db(path: &Path) -> Result<()> {
+        let db = Connection::open(path)?;
+        db.execute_batch(
+            r#"
+create table global_variables (key text, value text);
+insert into global_variables values ('version', '2');
+create table facts (_id integer primary key, id text);
+create table data_for_fact (_fact_id integer, key text, value text);
+create table cards (
+    _id integer primary key, id text, card_type_id text, _fact_id integer,
+    fact_view_id text, tags text, grade integer, next_rep integer,
+    last_rep integer, easiness real, acq_reps integer, ret_reps integer,
+    lapses integer
+);
+insert into facts values (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd');
+insert into data_for_fact values
+    (1, 'f', 'front'), (1, 'b', 'back'),
+    (2, 'f', 'question'), (2, 'b', 'answer'),
+    (3, 'f', 'word'), (3, 'p_1', 'pron'), (3, 'm_1', 'meaning'), (3, 'n', ''),
+    (4, 'text', 'a [b] c [d]');
+insert into cards values
+    (1, 'a1', '1', 1, '1.1', '__UNTAGGED__', -1, -1, -1, 2.5, 0, 0, 0),
+    (2, 'b1', '2', 2, '2.1', 'Languages::French, verb', 4,
+        strftime('%s', 'now') + 86400 * 5, strftime('%s', 'now') - 86400 * 5,
+        2.1, 3, 4, 1),
+    (3, 'b2', '2', 2, '2.2', 'Languages::French, verb', -1, -1, -1, 2.5, 0, 0, 0),
+    (4, 'c1', '3', 3, '3.1', 'vocab', -1, -1, -1, 2.5, 0, 0, 0),
+    (5, 'c2', '3', 3, '3.2', 'vocab', -1, -1, -1, 2.5, 0, 0, 0),
+    (6, 'd1', '5', 4, '5.1', '', -1, -1, -1, 2.5, 0, 0, 0);
+"#,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn mnemosyne_facts_become_notes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("default.db");
+        write_mnemosyne_db(&path)?;
+        let mut col = Collection::new();
+
+        let log = col.import_mnemosyne(&path)?.output;
+        assert_eq!(log.new.len(), 4);
+        assert_eq!(col.storage.get_all_notes().len(), 4);
+        // front only: 1, front/back: 2, vocabulary: 2, cloze with 2 deletions: 2
+        assert_eq!(col.search_cards("", SortMode::NoOrder)?.len(), 7);
+        for name in [
+            "Mnemosyne-FrontOnly",
+            "Mnemosyne-FrontBack",
+            "Mnemosyne-Vocabulary",
+            "Mnemosyne-Cloze",
+        ] {
+            assert!(col.get_notetype_by_name(name)?.is_some());
+        }
+
+        let did = col.get_deck_id("Languages::French")?.unwrap();
+        let cids = col.search_cards(SearchNode::from_deck_id(did, false), SortMode::NoOrder)?;
+        assert_eq!(cids.len(), 2);
+        let review = col.search_cards("is:review tag:verb", SortMode::NoOrder)?;
+        assert_eq!(review.len(), 1);
+        let card = col.storage.get_card(review[0])?.unwrap();
+        assert_eq!(card.interval, 10);
+        assert_eq!(card.ease_factor, 2100);
+        assert_eq!((card.reps, card.lapses), (7, 1));
+
+        let cloze = col.storage.get_all_notes().pop().unwrap();
+        assert_eq!(cloze.fields()[0], "a {{c1::b}} c {{c2::d}}");
+
+        // importing again reuses the existing notetypes
+        let ntcount = col.get_all_notetypes()?.len();
+        col.import_mnemosyne(&path)?;
+        assert_eq!(col.get_all_notetypes()?.len(), ntcount);
+        Ok(())
+    }
+}
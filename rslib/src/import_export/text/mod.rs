@@ -4,6 +4,9 @@
 pub mod csv;
 mod import;
 mod json;
+mod markdown;
+mod mnemosyne;
+mod supermemo;
 
 use anki_proto::import_export::csv_metadata::DupeResolution;
 use anki_proto::import_export::csv_metadata::MatchScope;
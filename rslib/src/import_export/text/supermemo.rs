@@ -0,0 +1,263 @@
+// Copyright: Ankitects Pty Ltd and contributors
+// License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
+
+//! Importing of SuperMemo's Q&A XML exports. Only questions and answers are
+//! imported; topics, learning data and media files are ignored, so all cards
+//! start as new.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use anki_io::read_to_string;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::import_export::text::ForeignData;
+use crate::import_export::text::ForeignNote;
+use crate::import_export::text::ForeignNotetype;
+use crate::import_export::text::ForeignTemplate;
+use crate::import_export::text::NameOrId;
+use crate::import_export::ImportError;
+use crate::import_export::NoteLog;
+use crate::prelude::*;
+use crate::text::decode_entities;
+use crate::text::CowMapping;
+
+const NOTETYPE_NAME: &str = "Basic";
+
+impl Collection {
+    pub fn import_supermemo_xml(&mut self, path: &Path, did: DeckId) -> Result<OpOutput<NoteLog>> {
+        let progress = self.new_progress_handler();
+        let xml = read_to_string(path)?;
+        let items = parse_supermemo_items(&xml)?;
+        let notetypes = if self.get_notetype_by_name(NOTETYPE_NAME)?.is_some() {
+            vec![]
+        } else {
+            vec![basic_notetype()]
+        };
+        let data = ForeignData {
+            default_deck: NameOrId::Id(did.0),
+            default_notetype: NameOrId::Name(NOTETYPE_NAME.to_string()),
+            notes: items
+                .into_iter()
+                .map(SuperMemoItem::into_foreign_note)
+                .collect(),
+            notetypes,
+            ..Default::default()
+        };
+        data.import(self, progress)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SuperMemoItem {
+    question: String,
+    answer: String,
+}
+
+impl SuperMemoItem {
+    fn is_complete(&self) -> bool {
+        !self.question.trim().is_empty() && !self.answer.trim().is_empty()
+    }
+
+    fn into_foreign_note(self) -> ForeignNote {
+        ForeignNote {
+            fields: vec![
+                Some(convert_field(&self.question)),
+                Some(convert_field(&self.answer)),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+fn basic_notetype() -> ForeignNotetype {
+    ForeignNotetype {
+        name: NOTETYPE_NAME.to_string(),
+        fields: vec!["Front".to_string(), "Back".to_string()],
+        templates: vec![ForeignTemplate {
+            name: "Card 1".to_string(),
+            qfmt: "{{Front}}".to_string(),
+            afmt: "{{FrontSide}}\n\n<hr id=answer>\n\n{{Back}}".to_string(),
+        }],
+        is_cloze: false,
+    }
+}
+
+/// Turns newlines into line breaks, and strips the (usually absolute) paths
+/// from image references, so they point into the media folder.
+fn convert_field(text: &str) -> String {
+    static IMG_PATH: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?i)(<img\b[^>]*?\bsrc=["'])[^"']*[\\/]([^"'\\/]+["'])"#).unwrap()
+    });
+    let text = text.trim().replace("\r\n", "\n").replace('\n', "<br>");
+    let text: Cow<str> = text.into();
+    text.map_cow(|s| IMG_PATH.replace_all(s, "$1$2"))
+        .into_owned()
+}
+
+fn corrupt() -> AnkiError {
+    AnkiError::ImportError {
+        source: ImportError::Corrupt,
+    }
+}
+
+/// Extracts the items from a SuperMemo XML export. This is not a general
+/// purpose XML parser, but it handles everything SuperMemo writes, and
+/// reports malformed or truncated files as corrupt.
+fn parse_supermemo_items(xml: &str) -> Result<Vec<SuperMemoItem>> {
+    let mut items = vec![];
+    let mut open_tags: Vec<&str> = vec![];
+    let mut open_items: Vec<SuperMemoItem> = vec![];
+    let mut found_collection = false;
+    let mut rest = xml;
+
+    while !rest.is_empty() {
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        let (text, tail) = rest.split_at(text_end);
+        append_text(&open_tags, &mut open_items, &decode_entities(text));
+        rest = tail;
+        if rest.is_empty() {
+            break;
+        }
+
+        if let Some(tail) = rest.strip_prefix("<![CDATA[") {
+            let (cdata, tail) = tail.split_once("]]>").ok_or_else(corrupt)?;
+            append_text(&open_tags, &mut open_items, cdata);
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("<!--") {
+            rest = tail.split_once("-->").ok_or_else(corrupt)?.1;
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            rest = rest.split_once('>').ok_or_else(corrupt)?.1;
+        } else {
+            let (tag, tail) = rest[1..].split_once('>').ok_or_else(corrupt)?;
+            rest = tail;
+            if let Some(name) = tag.strip_prefix('/') {
+                if open_tags.pop() != Some(name.trim()) {
+                    return Err(corrupt());
+                }
+                if name.trim() == "SuperMemoElement" {
+                    let item = open_items.pop().ok_or_else(corrupt)?;
+                    if item.is_complete() {
+                        items.push(item);
+                    }
+                }
+            } else {
+                let self_closing = tag.ends_with('/');
+                let name = tag
+                    .trim_end_matches('/')
+                    .split_whitespace()
+                    .next()
+                    .ok_or_else(corrupt)?;
+                match name {
+                    "SuperMemoCollection" => found_collection = true,
+                    "SuperMemoElement" if !self_closing => open_items.push(Default::default()),
+                    _ => (),
+                }
+                if !self_closing {
+                    open_tags.push(name);
+                }
+            }
+        }
+    }
+
+    if !found_collection || !open_tags.is_empty() {
+        return Err(corrupt());
+    }
+    Ok(items)
+}
+
+fn append_text(open_tags: &[&str], open_items: &mut [SuperMemoItem], text: &str) {
+    if let Some(item) = open_items.last_mut() {
+        match open_tags.last() {
+            Some(&"Question") => item.question.push_str(text),
+            Some(&"Answer") => item.answer.push_str(text),
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<SuperMemoCollection>
+  <Count>3</Count>
+  <SuperMemoElement>
+    <ID>1</ID>
+    <Title>Topic</Title>
+    <Type>Topic</Type>
+    <SuperMemoElement>
+      <ID>2</ID>
+      <Type>Item</Type>
+      <Content>
+        <Question>What is &lt;b&gt;bold&lt;/b&gt;?</Question>
+        <Answer>Strong &amp;amp; heavy
+&lt;img src="C:\sm\elements\1.jpg"&gt;</Answer>
+      </Content>
+      <LearningData>
+        <Interval>12</Interval>
+      </LearningData>
+    </SuperMemoElement>
+    <SuperMemoElement>
+      <ID>3</ID>
+      <Type>Item</Type>
+      <Content>
+        <Question>No answer</Question>
+        <Answer/>
+      </Content>
+    </SuperMemoElement>
+  </SuperMemoElement>
+</SuperMemoCollection>
+"#;
+
+    #[test]
+    fn parsing_items() -> Result<()> {
+        let items = parse_supermemo_items(XML)?;
+        assert_eq!(items.len(), 1);
+        let note = items.into_iter().next().unwrap().into_foreign_note();
+        assert_eq!(
+            note.fields,
+            [
+                Some("What is <b>bold</b>?".to_string()),
+                Some(r#"Strong &amp; heavy<br><img src="1.jpg">"#.to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_files_are_reported_as_corrupt() {
+        for xml in [
+            "",
+            "<html></html>",
+            "<SuperMemoCollection><SuperMemoElement>",
+            "<SuperMemoCollection></SuperMemoElement></SuperMemoCollection>",
+            "<SuperMemoCollection><SuperMemoElement",
+        ] {
+            assert!(matches!(
+                parse_supermemo_items(xml),
+                Err(AnkiError::ImportError {
+                    source: ImportError::Corrupt
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn importing_into_deck() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("export.xml");
+        anki_io::write_file(&path, XML)?;
+        let mut col = Collection::new();
+        let did = col.get_or_create_normal_deck("SuperMemo")?.id;
+
+        let log = col.import_supermemo_xml(&path, did)?.output;
+        assert_eq!(log.new.len(), 1);
+        let card = col.get_first_card();
+        assert_eq!(card.deck_id, did);
+        assert_eq!(card.ctype, CardType::New);
+        Ok(())
+    }
+}
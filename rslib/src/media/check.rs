@@ -11,6 +11,7 @@ use anki_i18n::without_unicode_isolation;
 use anki_io::write_file;
 use data_encoding::BASE64;
 use once_cell::sync::Lazy;
+use percent_encoding_iri::percent_decode_str;
 use regex::Regex;
 use tracing::debug;
 use tracing::info;
@@ -58,6 +59,107 @@ impl Collection {
     pub fn media_checker(&mut self) -> Result<MediaChecker<'_>> {
         MediaChecker::new(self)
     }
+
+    /// Returns the files in the media folder that are not referenced by any
+    /// note, using the same rules as the media check. Unlike the latter, no
+    /// files or notes are modified, so files with names the media check would
+    /// rename are not included.
+    pub fn unused_media_files(&mut self) -> Result<Vec<String>> {
+        let mut files = vec![];
+        for dentry in self.media_folder.read_dir()? {
+            let dentry = dentry?;
+            let fname_os = dentry.file_name();
+            let Some(fname) = fname_os.to_str().and_then(filename_if_normalized) else {
+                continue;
+            };
+            let metadata = dentry.metadata()?;
+            if metadata.is_dir()
+                || metadata.len() == 0
+                || metadata.len() > MAX_INDIVIDUAL_MEDIA_FILE_SIZE as u64
+            {
+                continue;
+            }
+            files.push(fname.into_owned());
+        }
+
+        let references = self.media_references()?;
+        let mut unused = UnusedAndMissingFiles::new(files, references).unused;
+        unused.sort_unstable();
+        Ok(unused)
+    }
+
+    /// Returns the notes that reference files which are not present in the
+    /// media folder, along with the missing filenames. Like
+    /// [Collection::unused_media_files], nothing is modified.
+    pub fn notes_with_missing_media(&mut self) -> Result<Vec<(NoteId, Vec<String>)>> {
+        let mut files = HashSet::new();
+        for dentry in self.media_folder.read_dir()? {
+            let fname_os = dentry?.file_name();
+            if let Some(fname) = fname_os.to_str() {
+                files.insert(normalize_to_nfc(fname).into_owned());
+            }
+        }
+
+        let mut missing: HashMap<NoteId, Vec<String>> = HashMap::new();
+        for (fname, nids) in self.media_references()? {
+            if !files.contains(&fname) {
+                for nid in nids {
+                    missing.entry(nid).or_default().push(fname.clone());
+                }
+            }
+        }
+
+        let mut missing: Vec<_> = missing.into_iter().collect();
+        for (_, fnames) in &mut missing {
+            fnames.sort_unstable();
+            fnames.dedup();
+        }
+        missing.sort_unstable();
+        Ok(missing)
+    }
+
+    /// Maps each file referenced by a note to the referencing notes.
+    fn media_references(&mut self) -> Result<HashMap<String, Vec<NoteId>>> {
+        let mut references: HashMap<String, Vec<NoteId>> = HashMap::new();
+        let notetypes = self.get_all_notetypes()?;
+        for nid in self.search_notes_unordered("")? {
+            let note = self.storage.get_note(nid)?.or_not_found(nid)?;
+            let nt = notetypes
+                .iter()
+                .find(|nt| nt.id == note.notetype_id)
+                .or_not_found(note.notetype_id)?;
+            note_media_refs(&note, nt.config.latex_svg, |fname| {
+                references.entry(fname).or_insert_with(Vec::new).push(nid)
+            });
+        }
+
+        Ok(references)
+    }
+}
+
+/// Calls `tracker` with the name of each local media file referenced by the
+/// note, including the images LaTeX is rendered to. Remote and inline
+/// references are skipped. The names match the ones the media check
+/// records.
+pub(crate) fn note_media_refs(note: &Note, latex_svg: bool, mut tracker: impl FnMut(String)) {
+    for field in note.fields() {
+        for media_ref in extract_media_refs(field) {
+            if REMOTE_FILENAME.is_match(media_ref.fname) || media_ref.fname.starts_with("data:") {
+                continue;
+            }
+            tracker(referenced_filename(&media_ref.fname_decoded));
+        }
+    }
+    extract_latex_refs(note, tracker, latex_svg);
+}
+
+/// The name of the file in the media folder a reference points to. URL
+/// escapes are decoded, and a trailing `?foo=bar`, which is sometimes used to
+/// bust caches, is stripped, as `?` is not valid in media filenames.
+fn referenced_filename(fname: &str) -> String {
+    let fname = percent_decode_str(fname).decode_utf8_lossy();
+    let fname = fname.split_once('?').map_or(&*fname, |(fname, _)| fname);
+    normalize_to_nfc(fname).into()
 }
 
 pub struct MediaChecker<'a> {
@@ -447,8 +549,8 @@ impl MediaChecker<'_> {
             if let Cow::Owned(ref new_name) = fname {
                 field = rename_media_ref_in_field(field.as_ref(), &media_ref, new_name).into();
             }
-            // and mark this filename as having been referenced
-            tracker(fname.into_owned());
+            // and mark the file it points to as having been referenced
+            tracker(referenced_filename(&fname));
         }
 
         Ok(field)
@@ -840,4 +942,76 @@ Unused: unused.jpg
 
         Ok(())
     }
+
+    #[test]
+    fn unused_media_files() -> Result<()> {
+        let (_dir, mgr, mut col) = common_setup()?;
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut note = nt.new_note();
+        note.set_field(0, "<img src='cached.jpg?v=2'>")?;
+        note.set_field(1, "[$]x^2[/$]")?;
+        col.add_note(&mut note, DeckId(1))?;
+        let (_, latex) = extract_latex_expanding_clozes(&note.fields()[1], false);
+
+        for fname in [
+            "cached.jpg",
+            "_template.js",
+            "unused.jpg",
+            latex[0].fname.as_str(),
+        ] {
+            write_file(mgr.media_folder.join(fname), "data")?;
+        }
+        assert_eq!(col.unused_media_files()?, ["unused.jpg"]);
+        // nothing should have been removed
+        assert!(mgr.media_folder.join("unused.jpg").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn percent_encoded_references_agree_with_media_check() -> Result<()> {
+        let (_dir, mgr, mut col) = common_setup()?;
+        let note = NoteAdder::basic(&mut col)
+            .fields(&["<img src=\"my%20pic.jpg\">", "<img src='cached.jpg?v=2'>"])
+            .add(&mut col);
+        for fname in ["my pic.jpg", "cached.jpg"] {
+            write_file(mgr.media_folder.join(fname), "data")?;
+        }
+
+        let mut checker = col.media_checker()?;
+        let mut seen = HashSet::new();
+        for field in note.fields() {
+            seen.extend(normalize_and_maybe_rename_files_helper(&mut checker, field));
+        }
+        let output = checker.check()?;
+        assert!(output.unused.is_empty());
+        assert_eq!(col.media_referenced_by_notes(&[note.id])?, seen);
+        assert!(col.unused_media_files()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn notes_with_missing_media() -> Result<()> {
+        let (_dir, mgr, mut col) = common_setup()?;
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut note = nt.new_note();
+        note.set_field(0, "<img src='present.jpg'><img src='gone.jpg'>")?;
+        note.set_field(1, "[sound:gone.mp3][sound:gone.mp3]")?;
+        col.add_note(&mut note, DeckId(1))?;
+        let mut complete = nt.new_note();
+        complete.set_field(0, "<img src='present.jpg'>")?;
+        col.add_note(&mut complete, DeckId(1))?;
+        write_file(mgr.media_folder.join("present.jpg"), "data")?;
+
+        assert_eq!(
+            col.notes_with_missing_media()?,
+            [(
+                note.id,
+                vec!["gone.jpg".to_string(), "gone.mp3".to_string()]
+            )]
+        );
+
+        Ok(())
+    }
 }
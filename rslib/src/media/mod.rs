@@ -7,12 +7,14 @@ mod service;
 
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 
 use anki_io::create_dir_all;
 use reqwest::Client;
 
+use crate::media::check::note_media_refs;
 use crate::media::files::add_data_to_folder_uniquely;
 use crate::media::files::mtime_as_i64;
 use crate::media::files::remove_files;
@@ -33,6 +35,32 @@ impl Collection {
     pub fn media(&self) -> Result<MediaManager> {
         MediaManager::new(&self.media_folder, &self.media_db)
     }
+
+    /// Names of the media files referenced by the fields of the provided
+    /// notes, found with the same rules as the media check.
+    pub fn media_referenced_by_notes(&self, nids: &[NoteId]) -> Result<HashSet<String>> {
+        let mut names = HashSet::new();
+        let mut svg_by_notetype = HashMap::new();
+        for &nid in nids {
+            let note = self.storage.get_note(nid)?.or_not_found(nid)?;
+            let svg = match svg_by_notetype.get(&note.notetype_id) {
+                Some(&svg) => svg,
+                None => {
+                    let notetype = self
+                        .storage
+                        .get_notetype(note.notetype_id)?
+                        .or_not_found(note.notetype_id)?;
+                    let svg = notetype.config.latex_svg;
+                    svg_by_notetype.insert(note.notetype_id, svg);
+                    svg
+                }
+            };
+            note_media_refs(&note, svg, |fname| {
+                names.insert(fname);
+            });
+        }
+        Ok(names)
+    }
 }
 
 pub struct MediaManager {
@@ -141,16 +169,19 @@ impl MediaManager {
         })
     }
 
-    /// Sync media.
+    /// Sync media. `batch_callback` is called after each batch of files is
+    /// downloaded.
     pub async fn sync_media(
         self,
         progress: ThrottlingProgressHandler<MediaSyncProgress>,
+        batch_callback: impl FnMut(MediaSyncProgress) + Send + 'static,
         auth: SyncAuth,
         client: Client,
         server_usn: Option<Usn>,
     ) -> Result<()> {
         let client = HttpSyncClient::new(auth, client);
-        let mut syncer = MediaSyncer::new(self, progress, client)?;
+        let mut syncer =
+            MediaSyncer::new(self, progress, client)?.with_batch_callback(batch_callback);
         syncer.sync(server_usn).await
     }
 
@@ -180,3 +211,38 @@ impl MediaManager {
         self.db.all_registered_checksums().unwrap()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::NoteAdder;
+
+    #[test]
+    fn media_referenced_by_notes() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_notetype();
+        let first = NoteAdder::new(&nt)
+            .fields(&[
+                "<img src='a.jpg'><img src=\"my%20file.png\">",
+                "[sound:b.mp3] <img src='https://example.com/c.jpg'> <img src='a.jpg?v=2'>",
+            ])
+            .add(&mut col);
+        let second = NoteAdder::new(&nt)
+            .fields(&["[latex]x[/latex]", "<audio src='d&amp;e.ogg'>"])
+            .add(&mut col);
+        NoteAdder::new(&nt)
+            .fields(&["<img src='unrelated.jpg'>", ""])
+            .add(&mut col);
+
+        let mut names: Vec<_> = col
+            .media_referenced_by_notes(&[first.id, second.id])?
+            .into_iter()
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names.len(), 5);
+        assert_eq!(names[..3], ["a.jpg", "b.mp3", "d&e.ogg"]);
+        assert!(names[3].starts_with("latex-"));
+        assert_eq!(names[4], "my file.png");
+        Ok(())
+    }
+}
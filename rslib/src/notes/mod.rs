@@ -13,7 +13,9 @@ use itertools::Itertools;
 use sha1::Digest;
 use sha1::Sha1;
 
+use crate::cloze::cloze_numbers_in_order_of_appearance;
 use crate::cloze::contains_cloze;
+use crate::cloze::renumber_clozes_in_string;
 use crate::define_newtype;
 use crate::error;
 use crate::error::AnkiError;
@@ -91,6 +93,31 @@ impl Collection {
         self.transact(Op::AddNote, |col| col.add_note_inner(note, did))
     }
 
+    /// Add a note, first copying the fields at the `sticky` ordinals from
+    /// `prev` if they are empty in `note`, as when adding notes in quick
+    /// succession.
+    pub fn add_note_with_sticky(
+        &mut self,
+        note: &mut Note,
+        did: DeckId,
+        sticky: &[usize],
+        prev: &Note,
+    ) -> Result<OpOutput<()>> {
+        require!(
+            prev.notetype_id == note.notetype_id,
+            "previous note has a different notetype"
+        );
+        for &ord in sticky {
+            require!(ord < note.fields.len(), "notetype has no field {ord}");
+            if let Some(value) = prev.fields.get(ord) {
+                if field_is_empty(&note.fields[ord]) {
+                    note.fields[ord] = value.clone();
+                }
+            }
+        }
+        self.add_note(note, did)
+    }
+
     pub fn add_notes(&mut self, requests: &mut [AddNoteRequest]) -> Result<OpOutput<()>> {
         self.transact(Op::AddNote, |col| {
             for request in requests {
@@ -588,6 +615,123 @@ impl Collection {
         })
     }
 
+    /// Return groups of notes of the given notetype whose field at `field_ord`
+    /// has the same content, compared the same way as the first-field
+    /// duplicate check. Groups and their nids are in ascending nid order.
+    pub fn find_duplicate_notes(
+        &self,
+        ntid: NotetypeId,
+        field_ord: usize,
+    ) -> Result<Vec<Vec<NoteId>>> {
+        let notetype = self.storage.get_notetype(ntid)?.or_not_found(ntid)?;
+        require!(
+            field_ord < notetype.fields.len(),
+            "notetype has no field {field_ord}"
+        );
+        let mut groups: HashMap<String, Vec<NoteId>> = HashMap::new();
+        for (nid, field) in self
+            .storage
+            .note_fields_with_possible_duplicates(ntid, field_ord)?
+        {
+            let stripped = strip_html_preserving_media_filenames(&field);
+            if !stripped.trim().is_empty() {
+                groups.entry(stripped.into_owned()).or_default().push(nid);
+            }
+        }
+        Ok(groups
+            .into_values()
+            .filter(|nids| nids.len() > 1)
+            .sorted_unstable()
+            .collect())
+    }
+
+    /// Return the ids of notes that currently have no cards, eg. after a
+    /// template or cloze change. They are in ascending nid order.
+    pub fn notes_without_cards(&self) -> Result<Vec<NoteId>> {
+        self.storage.note_ids_without_cards()
+    }
+
+    /// Recompute the sort field and first field checksum of notes matching the
+    /// search, eg. after their fields were changed directly in the database.
+    /// Returns the number of notes whose stored values were stale.
+    pub fn refresh_note_derived_fields(&mut self, search: &str) -> Result<OpOutput<usize>> {
+        self.transact(Op::UpdateNote, |col| {
+            col.refresh_note_derived_fields_inner(search)
+        })
+    }
+
+    fn refresh_note_derived_fields_inner(&mut self, search: &str) -> Result<usize> {
+        let usn = self.usn()?;
+        let normalize_text = self.get_config_bool(BoolKey::NormalizeNoteText);
+        let mut count = 0;
+        for nid in self.search_notes_unordered(search)? {
+            let original = self.storage.get_note(nid)?.or_not_found(nid)?;
+            let notetype = self
+                .get_notetype(original.notetype_id)?
+                .or_not_found(original.notetype_id)?;
+            let mut note = original.clone();
+            note.prepare_for_update(&notetype, normalize_text)?;
+            if note.sort_field != original.sort_field || note.checksum != original.checksum {
+                note.set_modified(usn);
+                self.update_note_undoable(&note, &original)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Renumber the clozes of a cloze note so they run from 1 without gaps,
+    /// in the order they first appear across the note's fields. Existing
+    /// cards are moved along with their cloze, so their scheduling is kept.
+    /// Cards whose cloze no longer exists get the numbers after the
+    /// remaining clozes.
+    pub fn renumber_clozes(&mut self, nid: NoteId) -> Result<OpOutput<()>> {
+        self.transact(Op::UpdateNote, |col| col.renumber_clozes_inner(nid))
+    }
+
+    fn renumber_clozes_inner(&mut self, nid: NoteId) -> Result<()> {
+        let mut note = self.storage.get_note(nid)?.or_not_found(nid)?;
+        let notetype = self
+            .get_notetype(note.notetype_id)?
+            .or_not_found(note.notetype_id)?;
+        require!(notetype.is_cloze(), "not a cloze note");
+
+        let mut numbers = vec![];
+        for field in note.fields() {
+            cloze_numbers_in_order_of_appearance(field, &mut numbers);
+        }
+        let cards = self.storage.all_cards_of_note(nid)?;
+        let without_cloze = cards
+            .iter()
+            .map(|card| card.template_idx + 1)
+            .filter(|ord| !numbers.contains(ord))
+            .sorted_unstable()
+            .collect_vec();
+        numbers.extend(without_cloze);
+        let map: HashMap<u16, u16> = numbers
+            .into_iter()
+            .enumerate()
+            .map(|(idx, ord)| (ord, idx as u16 + 1))
+            .filter(|(old, new)| old != new)
+            .collect();
+        if map.is_empty() {
+            return Ok(());
+        }
+
+        let usn = self.usn()?;
+        for mut card in cards {
+            if let Some(&ord) = map.get(&(card.template_idx + 1)) {
+                let original = card.clone();
+                card.template_idx = ord - 1;
+                self.update_card_inner(&mut card, original, usn)?;
+            }
+        }
+        for field in note.fields_mut() {
+            *field = renumber_clozes_in_string(field, &map);
+        }
+        self.update_note_inner(&mut note)
+    }
+
     fn is_duplicate(&self, first_field: &str, note: &Note) -> Result<bool> {
         let csum = field_checksum(first_field);
         Ok(self
@@ -657,6 +801,7 @@ mod test {
     use crate::error::Result;
     use crate::prelude::*;
     use crate::search::SortMode;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn test_base91() {
@@ -673,6 +818,121 @@ mod test {
         assert_eq!(field_checksum("今日"), 1464653051);
     }
 
+    #[test]
+    fn duplicate_notes() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut add = |front: &str, back: &str| -> Result<NoteId> {
+            let mut note = nt.new_note();
+            note.set_field(0, front)?;
+            note.set_field(1, back)?;
+            col.add_note(&mut note, DeckId(1))?;
+            Ok(note.id)
+        };
+        let a = add("one", "x")?;
+        let b = add("<b>one</b>", "y")?;
+        let c = add("two", "x")?;
+        add("One", "")?;
+        let e = add("two", "")?;
+
+        // formatting is ignored, case is not
+        assert_eq!(
+            col.find_duplicate_notes(nt.id, 0)?,
+            [vec![a, b], vec![c, e]]
+        );
+        // empty fields are not duplicates of each other
+        assert_eq!(col.find_duplicate_notes(nt.id, 1)?, [vec![a, c]]);
+        assert!(col.find_duplicate_notes(nt.id, 2).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn renumber_clozes() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Cloze")?.unwrap();
+        let mut note = nt.new_note();
+        note.set_field(0, "{{c1::a}} {{c3::b}} {{c5::c {{c8::d}}}} {{c3::e}}")?;
+        col.add_note(&mut note, DeckId(1))?;
+        let card_id_for_ord = |col: &Collection, ord: u16| -> Result<CardId> {
+            Ok(col
+                .storage
+                .all_cards_of_note(note.id)?
+                .into_iter()
+                .find(|card| card.template_idx == ord)
+                .unwrap()
+                .id)
+        };
+        let c3 = card_id_for_ord(&col, 2)?;
+        let c8 = card_id_for_ord(&col, 7)?;
+
+        col.renumber_clozes(note.id)?;
+        let note = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(
+            note.fields()[0],
+            "{{c1::a}} {{c2::b}} {{c3::c {{c4::d}}}} {{c2::e}}"
+        );
+        // existing cards were moved, and no extra ones were added
+        assert_eq!(col.storage.all_cards_of_note(note.id)?.len(), 4);
+        assert_eq!(card_id_for_ord(&col, 1)?, c3);
+        assert_eq!(card_id_for_ord(&col, 3)?, c8);
+
+        // contiguous clozes are left alone
+        let changes = col.renumber_clozes(note.id)?.changes;
+        assert!(!changes.changes.note);
+
+        // only cloze notes can be renumbered
+        let nid = NoteAdder::basic(&mut col).add(&mut col).id;
+        assert!(col.renumber_clozes(nid).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sticky_fields() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut prev = nt.new_note();
+        prev.set_field(0, "front")?;
+        prev.set_field(1, "source")?;
+        col.add_note(&mut prev, DeckId(1))?;
+
+        let mut note = nt.new_note();
+        note.set_field(0, "new front")?;
+        col.add_note_with_sticky(&mut note, DeckId(1), &[0, 1], &prev)?;
+        let note = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(note.fields(), &["new front", "source"]);
+
+        let mut note = nt.new_note();
+        assert!(col
+            .add_note_with_sticky(&mut note, DeckId(1), &[2], &prev)
+            .is_err());
+        let other = col.get_notetype_by_name("Cloze")?.unwrap().new_note();
+        assert!(col
+            .add_note_with_sticky(&mut note, DeckId(1), &[1], &other)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn notes_without_cards() -> Result<()> {
+        let mut col = Collection::new();
+        let a = NoteAdder::basic(&mut col).add(&mut col).id;
+        NoteAdder::basic(&mut col).add(&mut col);
+        let c = NoteAdder::basic(&mut col).add(&mut col).id;
+        assert!(col.notes_without_cards()?.is_empty());
+
+        for nid in [c, a] {
+            for cid in col.storage.card_ids_of_notes(&[nid])? {
+                col.storage.remove_card(cid)?;
+            }
+        }
+        assert_eq!(col.notes_without_cards()?, [a, c]);
+
+        Ok(())
+    }
+
     #[test]
     fn adding_cards() -> Result<()> {
         let mut col = Collection::new();
@@ -822,4 +1082,39 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn refreshing_derived_fields() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_notetype();
+        let stale = NoteAdder::new(&nt)
+            .fields(&["<b>front</b>", ""])
+            .add(&mut col);
+        let fresh = NoteAdder::new(&nt).fields(&["other", ""]).add(&mut col);
+        let derived = |col: &Collection, nid: NoteId| -> Result<(String, u32)> {
+            Ok(col.storage.db.query_row(
+                "select sfld, csum from notes where id = ?",
+                [nid],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?)
+        };
+        let expected = derived(&col, stale.id)?;
+        col.storage.db.execute(
+            "update notes set sfld = 'x', csum = 0 where id = ?",
+            [stale.id],
+        )?;
+
+        assert_eq!(col.refresh_note_derived_fields("")?.output, 1);
+        assert_eq!(derived(&col, stale.id)?, expected);
+        assert_eq!(expected, ("front".to_string(), field_checksum("front")));
+        col.undo()?;
+        assert_eq!(derived(&col, stale.id)?, ("x".to_string(), 0));
+
+        // notes not matching the search are left alone
+        let search = format!("nid:{}", fresh.id);
+        assert_eq!(col.refresh_note_derived_fields(&search)?.output, 0);
+        assert_eq!(derived(&col, stale.id)?, ("x".to_string(), 0));
+
+        Ok(())
+    }
 }
@@ -11,6 +11,7 @@ use super::NotetypeId;
 use super::NotetypeKind;
 use crate::card::CardId;
 use crate::collection::Collection;
+use crate::error::OrNotFound;
 use crate::error::Result;
 use crate::notes::NoteId;
 
@@ -21,6 +22,18 @@ pub struct EmptyCardsForNote {
     pub current_count: usize,
 }
 
+/// A card that would be removed by [Collection::remove_empty_cards].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyCardInfo {
+    pub card_id: CardId,
+    pub note_id: NoteId,
+    pub notetype_name: String,
+    pub template_ord: u32,
+    /// True if all of the note's cards are empty, so removing them would
+    /// remove the note as well.
+    pub all_cards_empty: bool,
+}
+
 impl Collection {
     fn empty_cards_for_notetype(&self, nt: &Notetype) -> Result<Vec<EmptyCardsForNote>> {
         let last_deck = self.get_last_deck_added_to_for_notetype(nt.id);
@@ -67,6 +80,54 @@ impl Collection {
             .collect()
     }
 
+    /// List empty cards without removing them. Cards of notes that have only
+    /// empty cards are listed after all others.
+    pub fn empty_card_report(&mut self) -> Result<Vec<EmptyCardInfo>> {
+        let mut partly_empty = vec![];
+        let mut all_empty = vec![];
+        for (ntid, notes) in self.empty_cards()? {
+            let notetype_name = self.get_notetype(ntid)?.or_not_found(ntid)?.name.clone();
+            for mut note in notes {
+                note.empty.sort_unstable();
+                let all_cards_empty = note.empty.len() == note.current_count;
+                let out = if all_cards_empty {
+                    &mut all_empty
+                } else {
+                    &mut partly_empty
+                };
+                out.extend(note.empty.into_iter().map(|(ord, cid)| EmptyCardInfo {
+                    card_id: cid,
+                    note_id: note.nid,
+                    notetype_name: notetype_name.clone(),
+                    template_ord: ord,
+                    all_cards_empty,
+                }));
+            }
+        }
+        partly_empty.append(&mut all_empty);
+        Ok(partly_empty)
+    }
+
+    /// Remove cards listed by [Collection::empty_card_report]. If `keep_notes`
+    /// is true, notes with only empty cards keep their first card instead of
+    /// being removed. Returns the number of removed cards.
+    pub fn remove_empty_cards(
+        &mut self,
+        cards: &[EmptyCardInfo],
+        keep_notes: bool,
+    ) -> Result<usize> {
+        let mut kept_notes = HashSet::new();
+        let mut cids = vec![];
+        for card in cards {
+            if keep_notes && card.all_cards_empty && kept_notes.insert(card.note_id) {
+                continue;
+            }
+            cids.push(card.card_id);
+        }
+        self.transact_no_undo(|col| col.remove_cards_and_orphaned_notes(&cids))?;
+        Ok(cids.len())
+    }
+
     /// Create a report on empty cards. Mutates the provided data to sort
     /// ordinals.
     pub fn empty_cards_report(
@@ -137,3 +198,55 @@ impl Collection {
         Ok(buf)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::NoteAdder;
+
+    #[test]
+    fn empty_card_report() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_rev_notetype();
+        let all_empty = NoteAdder::new(&nt).add(&mut col);
+        let mut partly_empty = NoteAdder::new(&nt).fields(&["front", "back"]).add(&mut col);
+        partly_empty.set_field(1, "")?;
+        col.update_note(&mut partly_empty)?;
+        let card_id = |nid, ord| {
+            col.storage
+                .all_cards_of_note(nid)
+                .unwrap()
+                .into_iter()
+                .find(|c| c.template_idx == ord)
+                .unwrap()
+                .id
+        };
+        let expected = [
+            EmptyCardInfo {
+                card_id: card_id(partly_empty.id, 1),
+                note_id: partly_empty.id,
+                notetype_name: nt.name.clone(),
+                template_ord: 1,
+                all_cards_empty: false,
+            },
+            EmptyCardInfo {
+                card_id: card_id(all_empty.id, 0),
+                note_id: all_empty.id,
+                notetype_name: nt.name.clone(),
+                template_ord: 0,
+                all_cards_empty: true,
+            },
+        ];
+
+        let report = col.empty_card_report()?;
+        assert_eq!(report, expected);
+        // nothing is removed until asked
+        assert_eq!(col.storage.get_all_card_ids()?.len(), 3);
+
+        assert_eq!(col.remove_empty_cards(&report, true)?, 1);
+        assert_eq!(col.storage.get_all_card_ids()?.len(), 2);
+        assert!(col.storage.get_note(all_empty.id)?.is_some());
+
+        Ok(())
+    }
+}
@@ -72,6 +72,34 @@ impl NoteField {
     }
 }
 
+impl Collection {
+    /// Set the font, font size and text direction the editor uses for a
+    /// field. These are stored in the field's config, so they sync with the
+    /// rest of the notetype.
+    pub fn set_field_display(
+        &mut self,
+        ntid: NotetypeId,
+        field_ord: usize,
+        font: &str,
+        size: u16,
+        rtl: bool,
+    ) -> Result<OpOutput<()>> {
+        let mut notetype = self
+            .get_notetype(ntid)?
+            .or_not_found(ntid)?
+            .as_ref()
+            .clone();
+        let field = notetype
+            .fields
+            .get_mut(field_ord)
+            .or_invalid("field ordinal out of range")?;
+        field.config.font_name = font.into();
+        field.config.font_size = size.into();
+        field.config.rtl = rtl;
+        self.update_notetype(&mut notetype, true)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -82,4 +110,22 @@ mod test {
         assert_eq!(field.fix_name(), Ok(()));
         assert_eq!(&field.name, "test field name #/^");
     }
+
+    #[test]
+    fn set_field_display() -> Result<()> {
+        let mut col = Collection::new();
+        let ntid = col.get_notetype_by_name("Basic")?.unwrap().id;
+        col.set_field_display(ntid, 1, "Noto Naskh Arabic", 32, true)?;
+
+        let nt = col.get_notetype(ntid)?.unwrap();
+        let config = &nt.fields[1].config;
+        assert_eq!(
+            (config.font_name.as_str(), config.font_size, config.rtl),
+            ("Noto Naskh Arabic", 32, true)
+        );
+        assert_eq!(nt.fields[0].config.font_name, "Arial");
+        assert!(col.set_field_display(ntid, 2, "Arial", 20, false).is_err());
+
+        Ok(())
+    }
 }
@@ -32,6 +32,7 @@ pub use anki_proto::notetypes::notetype::Template as CardTemplateProto;
 pub use anki_proto::notetypes::Notetype as NotetypeProto;
 pub(crate) use cardgen::AlreadyGeneratedCardInfo;
 pub(crate) use cardgen::CardGenContext;
+pub use emptycards::EmptyCardInfo;
 pub use fields::NoteField;
 use lazy_static::lazy_static;
 pub use notetypechange::ChangeNotetypeInput;
@@ -91,6 +92,16 @@ pub struct Notetype {
     pub config: NotetypeConfig,
 }
 
+/// The structure of a notetype, without its templates' content or config.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NotetypeSummary {
+    pub id: NotetypeId,
+    pub name: String,
+    pub kind: NotetypeKind,
+    pub field_names: Vec<String>,
+    pub template_names: Vec<String>,
+}
+
 impl Default for Notetype {
     fn default() -> Self {
         Notetype {
@@ -155,6 +166,62 @@ impl Collection {
         })
     }
 
+    /// Add a copy of an existing notetype's fields, templates and styling
+    /// under a new name, which is made unique in the same way as when adding
+    /// a notetype. The final name can be read from the added notetype. No
+    /// notes are changed.
+    pub fn clone_notetype(
+        &mut self,
+        ntid: NotetypeId,
+        new_name: &str,
+    ) -> Result<OpOutput<NotetypeId>> {
+        let mut notetype = self
+            .get_notetype(ntid)?
+            .or_not_found(ntid)?
+            .as_ref()
+            .clone();
+        notetype.name = new_name.into();
+        notetype.reset_ids_for_copy();
+        self.transact(Op::AddNotetype, |col| {
+            let usn = col.usn()?;
+            notetype.set_modified(usn);
+            col.add_notetype_inner(&mut notetype, usn, false)?;
+            Ok(notetype.id)
+        })
+    }
+
+    /// Serialize a notetype's fields, templates, styling and config to JSON,
+    /// using the same structure as the legacy notetype JSON. The result can
+    /// be passed to [Collection::import_notetype_json()].
+    pub fn export_notetype_json(&mut self, ntid: NotetypeId) -> Result<String> {
+        let mut notetype = self
+            .get_notetype(ntid)?
+            .or_not_found(ntid)?
+            .as_ref()
+            .clone();
+        // not meaningful outside of this collection
+        notetype.config.target_deck_id_unused = 0;
+        notetype.config.original_id = None;
+        let schema11: NotetypeSchema11 = notetype.into();
+        Ok(serde_json::to_string_pretty(&schema11)?)
+    }
+
+    /// Add a notetype from JSON produced by
+    /// [Collection::export_notetype_json()]. It is assigned a new id, and its
+    /// name is made unique in the same way as when adding a notetype.
+    pub fn import_notetype_json(&mut self, json: &str) -> Result<OpOutput<NotetypeId>> {
+        let schema11: NotetypeSchema11 = serde_json::from_str(json)?;
+        let mut notetype: Notetype = schema11.into();
+        notetype.config.target_deck_id_unused = 0;
+        notetype.reset_ids_for_copy();
+        self.transact(Op::AddNotetype, |col| {
+            let usn = col.usn()?;
+            notetype.set_modified(usn);
+            col.add_notetype_inner(&mut notetype, usn, false)?;
+            Ok(notetype.id)
+        })
+    }
+
     /// Saves changes to a note type. This will force a full sync if templates
     /// or fields have been added/removed/reordered.
     ///
@@ -224,6 +291,12 @@ impl Collection {
             .collect()
     }
 
+    /// The id, name, kind, and field and template names of every notetype,
+    /// sorted by name.
+    pub fn notetype_summaries(&self) -> Result<Vec<NotetypeSummary>> {
+        self.storage.get_notetype_summaries()
+    }
+
     pub fn get_all_notetypes_of_search_notes(
         &mut self,
     ) -> Result<HashMap<NotetypeId, Arc<Notetype>>> {
@@ -267,6 +340,22 @@ impl Collection {
 }
 
 impl Notetype {
+    /// Prepare a copy of this notetype for being added as a new notetype,
+    /// by clearing its id and assigning fresh ordinals and field/template
+    /// ids.
+    fn reset_ids_for_copy(&mut self) {
+        self.id = NotetypeId(0);
+        self.config.original_id = None;
+        for (ord, field) in self.fields.iter_mut().enumerate() {
+            field.ord = Some(ord as u32);
+            field.config.id = Some(rand::random());
+        }
+        for (ord, template) in self.templates.iter_mut().enumerate() {
+            template.ord = Some(ord as u32);
+            template.config.id = Some(rand::random());
+        }
+    }
+
     pub(crate) fn ensure_names_unique(&mut self) {
         let mut names = HashSet::new();
         for t in &mut self.templates {
@@ -375,6 +464,7 @@ impl Notetype {
                 if !CARD_TAG.is_match(&card.config.q_format) {
                     return Err(CardTypeError {
                         notetype: self.name.clone(),
+                        notetype_id: self.id,
                         ordinal: index,
                         source: CardTypeErrorDetails::Duplicate { index: old_index },
                     });
@@ -391,10 +481,11 @@ impl Notetype {
         &self,
         templates: &[(Option<ParsedTemplate>, Option<ParsedTemplate>)],
     ) -> Result<(), CardTypeError> {
-        for (ordinal, sides) in templates.iter().enumerate() {
-            self.ensure_valid_parsed_card_templates(sides)
+        for (ordinal, (template, sides)) in self.templates.iter().zip(templates).enumerate() {
+            self.ensure_valid_parsed_card_templates(template, sides)
                 .context(CardTypeSnafu {
                     notetype: &self.name,
+                    notetype_id: self.id,
                     ordinal,
                 })?;
         }
@@ -403,6 +494,7 @@ impl Notetype {
 
     fn ensure_valid_parsed_card_templates(
         &self,
+        template: &CardTemplate,
         sides: &(Option<ParsedTemplate>, Option<ParsedTemplate>),
     ) -> Result<(), CardTypeErrorDetails> {
         if let (Some(q), Some(a)) = sides {
@@ -419,7 +511,7 @@ impl Notetype {
             }
             Ok(())
         } else {
-            Err(CardTypeErrorDetails::TemplateParseError)
+            template.parse_error().map_or(Ok(()), Err)
         }
     }
 
@@ -445,6 +537,7 @@ impl Notetype {
         if self.is_cloze() && missing_cloze_filter(parsed_templates) {
             MissingClozeSnafu.fail().context(CardTypeSnafu {
                 notetype: &self.name,
+                notetype_id: self.id,
                 ordinal: 0usize,
             })
         } else {
@@ -481,8 +574,21 @@ impl Notetype {
         existing: Option<&Notetype>,
         skip_checks: bool,
     ) -> Result<()> {
-        require!(!self.fields.is_empty(), "1 field required");
-        require!(!self.templates.is_empty(), "1 template required");
+        require!(
+            !self.fields.is_empty(),
+            "notetype '{}' must have at least one field",
+            self.name
+        );
+        require!(
+            !self.templates.is_empty(),
+            "notetype '{}' must have at least one template",
+            self.name
+        );
+        require!(
+            !self.is_cloze() || self.templates.len() == 1,
+            "cloze notetype '{}' must have exactly one template",
+            self.name
+        );
         let bad_chars = |c| c == '"';
         if self.name.contains(bad_chars) {
             self.name = self.name.replace(bad_chars, "");
@@ -779,6 +885,8 @@ impl Collection {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::error::TemplateError;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn update_templates_after_removing_crucial_fields() {
@@ -841,4 +949,163 @@ mod test {
         assert_eq!(nt_cloze.templates[0].config.q_format, "front {{cloze:foo}}");
         assert_eq!(nt_cloze.templates[0].config.a_format, "back {{cloze:foo}}");
     }
+
+    #[test]
+    fn clone_notetype() -> Result<()> {
+        let mut col = Collection::new();
+        let original = col
+            .get_notetype_by_name("Basic (and reversed card)")?
+            .unwrap();
+        let nid = NoteAdder::new(&original).add(&mut col).id;
+
+        let ntid = col
+            .clone_notetype(original.id, "Basic (and reversed card)")?
+            .output;
+        let clone = col.get_notetype(ntid)?.unwrap();
+        assert_ne!(clone.id, original.id);
+        assert_eq!(clone.name, "Basic (and reversed card)+");
+        assert_eq!(clone.config.css, original.config.css);
+        let names = |nt: &Notetype| -> Vec<(Option<u32>, String)> {
+            nt.fields
+                .iter()
+                .map(|f| (f.ord, f.name.clone()))
+                .chain(nt.templates.iter().map(|t| (t.ord, t.name.clone())))
+                .collect()
+        };
+        assert_eq!(names(&clone), names(&original));
+        assert_eq!(
+            clone.templates[1].config.a_format,
+            original.templates[1].config.a_format
+        );
+        assert_ne!(clone.fields[0].config.id, original.fields[0].config.id);
+        // notes stay with the original notetype
+        assert_eq!(
+            col.search_notes_unordered(SearchNode::NotetypeId(original.id))?,
+            [nid]
+        );
+        assert!(col
+            .search_notes_unordered(SearchNode::NotetypeId(ntid))?
+            .is_empty());
+        assert!(col.clone_notetype(NotetypeId(1), "x").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn notetype_json_roundtrip() -> Result<()> {
+        let mut col = Collection::new();
+        let mut original = col.basic_rev_notetype();
+        original.config.css = ".card { color: red; }".into();
+        original.config.sort_field_idx = 1;
+        col.update_notetype(&mut original, false)?;
+
+        let json = col.export_notetype_json(original.id)?;
+        let ntid = col.import_notetype_json(&json)?.output;
+        let imported = col.get_notetype(ntid)?.unwrap();
+        assert_ne!(imported.id, original.id);
+        assert_eq!(imported.name, format!("{}+", original.name));
+        assert_eq!(imported.config.css, original.config.css);
+        assert_eq!(imported.config.sort_field_idx, 1);
+        assert_eq!(imported.fields.len(), original.fields.len());
+        assert_eq!(imported.templates.len(), original.templates.len());
+        assert_eq!(
+            imported.templates[1].config.q_format,
+            original.templates[1].config.q_format
+        );
+        assert_ne!(imported.fields[0].config.id, original.fields[0].config.id);
+
+        assert!(col.import_notetype_json("{}").is_err());
+        assert!(col.export_notetype_json(NotetypeId(1)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn notetype_summaries() -> Result<()> {
+        let col = Collection::new();
+        let summaries = col.notetype_summaries()?;
+        let names: Vec<_> = summaries.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names[..2], ["Basic", "Basic (and reversed card)"]);
+        assert!(names.windows(2).all(|w| w[0] < w[1]));
+
+        let reversed = &summaries[1];
+        assert_eq!(reversed.kind, NotetypeKind::Normal);
+        assert_eq!(reversed.field_names, ["Front", "Back"]);
+        assert_eq!(reversed.template_names, ["Card 1", "Card 2"]);
+        let cloze = summaries.iter().find(|s| s.name == "Cloze").unwrap();
+        assert_eq!(cloze.kind, NotetypeKind::Cloze);
+        assert_eq!(cloze.template_names, ["Cloze"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn notetypes_without_fields_or_templates_are_rejected() -> Result<()> {
+        let mut col = Collection::new();
+        let basic = col.get_notetype_by_name("Basic")?.unwrap().as_ref().clone();
+        let invalid = |msg: &str| AnkiError::InvalidInput {
+            source: snafu::FromString::without_source(msg.into()),
+        };
+
+        let mut nt = basic.clone();
+        nt.templates.clear();
+        assert_eq!(
+            col.update_notetype(&mut nt, false).unwrap_err(),
+            invalid("notetype 'Basic' must have at least one template")
+        );
+
+        let mut nt = basic.clone();
+        nt.fields.clear();
+        assert_eq!(
+            col.update_notetype(&mut nt, false).unwrap_err(),
+            invalid("notetype 'Basic' must have at least one field")
+        );
+
+        let mut nt = basic;
+        nt.id = NotetypeId(0);
+        nt.name = "new".into();
+        nt.templates.clear();
+        assert_eq!(
+            col.add_notetype(&mut nt, false).unwrap_err(),
+            invalid("notetype 'new' must have at least one template")
+        );
+
+        let mut nt = col.get_notetype_by_name("Cloze")?.unwrap().as_ref().clone();
+        nt.add_template("Cloze 2", "{{cloze:Text}}", "{{cloze:Text}}");
+        assert_eq!(
+            col.update_notetype(&mut nt, false).unwrap_err(),
+            invalid("cloze notetype 'Cloze' must have exactly one template")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn template_parse_errors_are_located() -> Result<()> {
+        let mut col = Collection::new();
+        let mut nt = col.get_notetype_by_name("Basic")?.unwrap().as_ref().clone();
+        nt.add_template("Card 2", "{{Back}}", "{{#Front}}{{Back}}{{/Bar}}");
+        let err = col.update_notetype(&mut nt, false).unwrap_err();
+        assert_eq!(
+            err,
+            AnkiError::CardTypeError {
+                source: CardTypeError {
+                    notetype: "Basic".into(),
+                    notetype_id: nt.id,
+                    ordinal: 1,
+                    source: CardTypeErrorDetails::TemplateParseError {
+                        question: false,
+                        error: TemplateError::ConditionalNotOpen {
+                            closed: "Bar".into(),
+                            currently_open: Some("Front".into()),
+                            offset: 18,
+                        },
+                    },
+                },
+            }
+        );
+        assert!(err.message(&col.tr).contains("{{/Bar}}"));
+
+        Ok(())
+    }
 }
@@ -117,6 +117,83 @@ impl Collection {
             col.change_notetype_of_notes_inner(input)
         })
     }
+
+    /// Move the provided notes to `target`, which may be the notetype they
+    /// already use. `field_map[new_ord]` is the old field ordinal each new
+    /// field should be filled from, or None to leave it blank. `template_map`
+    /// does the same for card templates, and determines which existing cards
+    /// are kept (with their scheduling) and which are removed; it must be
+    /// empty if either notetype is a cloze type, as cloze cards are matched by
+    /// number instead. Returns the number of cards that were removed.
+    pub fn change_notes_notetype(
+        &mut self,
+        nids: &[NoteId],
+        target: NotetypeId,
+        field_map: &[Option<usize>],
+        template_map: &[Option<usize>],
+    ) -> Result<OpOutput<usize>> {
+        let Some(first) = nids.first() else {
+            invalid_input!("no notes provided");
+        };
+        let old_notetype_id = self
+            .storage
+            .get_note(*first)?
+            .or_not_found(*first)?
+            .notetype_id;
+        let old_notetype = self
+            .get_notetype(old_notetype_id)?
+            .or_not_found(old_notetype_id)?;
+        let new_notetype = self.get_notetype(target)?.or_not_found(target)?;
+        for nid in nids {
+            let note = self.storage.get_note(*nid)?.or_not_found(*nid)?;
+            require!(
+                note.notetype_id == old_notetype_id,
+                "notes must all share the same notetype"
+            );
+        }
+        validate_ord_map(
+            field_map,
+            new_notetype.fields.len(),
+            old_notetype.fields.len(),
+        )?;
+        let new_templates = if old_notetype.config.kind() == NotetypeKind::Cloze
+            || new_notetype.config.kind() == NotetypeKind::Cloze
+        {
+            require!(
+                template_map.is_empty(),
+                "templates can't be mapped to or from a cloze notetype"
+            );
+            None
+        } else {
+            validate_ord_map(
+                template_map,
+                new_notetype.templates.len(),
+                old_notetype.templates.len(),
+            )?;
+            Some(template_map.to_vec())
+        };
+
+        let input = ChangeNotetypeInput {
+            current_schema: self.storage.get_collection_timestamps()?.schema_change,
+            note_ids: nids.to_vec(),
+            old_notetype_name: old_notetype.name.clone(),
+            old_notetype_id,
+            new_notetype_id: target,
+            new_fields: field_map.to_vec(),
+            new_templates,
+        };
+        let existing_cards = self.storage.card_ids_of_notes(nids)?;
+        self.transact(Op::ChangeNotetype, |col| {
+            col.change_notetype_of_notes_inner(input)?;
+            let mut removed = 0;
+            for cid in existing_cards {
+                if col.storage.get_card(cid)?.is_none() {
+                    removed += 1;
+                }
+            }
+            Ok(removed)
+        })
+    }
 }
 
 fn default_template_map(
@@ -359,6 +436,20 @@ impl Collection {
     }
 }
 
+/// Ensure a new->old ordinal mapping covers every new ordinal, and only
+/// refers to old ordinals that exist.
+fn validate_ord_map(map: &[Option<usize>], new_count: usize, old_count: usize) -> Result<()> {
+    require!(
+        map.len() == new_count,
+        "expected {new_count} entries in mapping, got {}",
+        map.len()
+    );
+    if let Some(idx) = map.iter().flatten().find(|idx| **idx >= old_count) {
+        invalid_input!("ordinal {idx} out of range");
+    }
+    Ok(())
+}
+
 /// Rewrite the field list from a note to match a new notetype's fields.
 fn remap_fields(fields: &mut Vec<String>, new_fields: &[Option<usize>]) {
     *fields = new_fields
@@ -553,4 +644,52 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn explicit_mapping() -> Result<()> {
+        let mut col = Collection::new();
+        let basic2 = col
+            .get_notetype_by_name("Basic (and reversed card)")?
+            .unwrap();
+        let mut note = basic2.new_note();
+        note.set_field(0, "front")?;
+        note.set_field(1, "back")?;
+        col.add_note(&mut note, DeckId(1))?;
+        let reverse_card = col.storage.all_cards_of_note(note.id)?[1].clone();
+
+        let basic = col.get_notetype_by_name("Basic")?.unwrap();
+        // out of range and wrongly-sized mappings are rejected
+        assert!(col
+            .change_notes_notetype(&[note.id], basic.id, &[Some(2), None], &[Some(1)])
+            .is_err());
+        assert!(col
+            .change_notes_notetype(&[note.id], basic.id, &[Some(1)], &[Some(1)])
+            .is_err());
+
+        // swap the fields, and keep only the reverse card
+        let removed = col
+            .change_notes_notetype(&[note.id], basic.id, &[Some(1), Some(0)], &[Some(1)])?
+            .output;
+        assert_eq!(removed, 1);
+        let note = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(note.notetype_id, basic.id);
+        assert_eq!(note.fields(), &["back", "front"]);
+        let cards = col.storage.all_cards_of_note(note.id)?;
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].id, reverse_card.id);
+        assert_eq!(cards[0].template_idx, 0);
+
+        // templates can't be mapped to a cloze notetype
+        let cloze = col.get_notetype_by_name("Cloze")?.unwrap();
+        assert!(col
+            .change_notes_notetype(&[note.id], cloze.id, &[Some(0), Some(1)], &[Some(0)])
+            .is_err());
+        assert_eq!(
+            col.change_notes_notetype(&[note.id], cloze.id, &[Some(0), Some(1)], &[])?
+                .output,
+            0
+        );
+
+        Ok(())
+    }
 }
@@ -88,6 +88,35 @@ impl Collection {
         self.render_card(note, &card, &nt, template, false, partial_render)
     }
 
+    /// Render the question and answer of a notetype's template for the
+    /// provided field contents, without a note needing to exist. Fields not
+    /// included in `fields` are treated as empty. For cloze notetypes,
+    /// `template_ord` is the cloze ordinal to render, starting at 0.
+    pub fn render_card_for_fields(
+        &mut self,
+        ntid: NotetypeId,
+        template_ord: usize,
+        fields: &HashMap<String, String>,
+    ) -> Result<RenderCardOutput> {
+        let nt = self.get_notetype(ntid)?.or_not_found(ntid)?;
+        let template = match nt.config.kind() {
+            NotetypeKind::Normal => nt.templates.get(template_ord),
+            NotetypeKind::Cloze => nt.templates.first(),
+        }
+        .or_invalid("missing template")?;
+        let mut note = Note::new(&nt);
+        for (name, value) in fields {
+            let idx = nt.get_field_ord(name).or_invalid(name)?;
+            note.set_field(idx, value)?;
+        }
+        let card = Card {
+            template_idx: template_ord as u16,
+            ..Default::default()
+        };
+
+        self.render_card(&note, &card, &nt, template, false, false)
+    }
+
     fn existing_or_synthesized_card(
         &self,
         nid: NoteId,
@@ -231,4 +260,49 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn render_for_fields() -> Result<()> {
+        let mut col = CollectionBuilder::default().build()?;
+        let mut nt = col.get_notetype_by_name("Basic")?.unwrap().as_ref().clone();
+        nt.templates[0].config.q_format = "{{Front}}{{#Back}}+{{/Back}}".into();
+        nt.templates[0].config.a_format = "{{hint:Back}}".into();
+        col.update_notetype(&mut nt, false)?;
+        let fields = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        // missing fields are empty
+        let out = col.render_card_for_fields(nt.id, 0, &fields(&[("Front", "front")]))?;
+        assert_eq!(&out.question(), "front");
+        let out =
+            col.render_card_for_fields(nt.id, 0, &fields(&[("Front", "front"), ("Back", "back")]))?;
+        assert_eq!(&out.question(), "front+");
+        assert!(out.answer().contains("back"));
+        // fields must exist on the notetype, and so must the template
+        assert!(col
+            .render_card_for_fields(nt.id, 0, &fields(&[("Nope", "")]))
+            .is_err());
+        assert!(col.render_card_for_fields(nt.id, 1, &fields(&[])).is_err());
+
+        // unknown field references are template errors, as in normal rendering
+        nt.templates[0].config.q_format = "{{Front}}{{Nope}}".into();
+        col.update_notetype(&mut nt, false)?;
+        assert!(matches!(
+            col.render_card_for_fields(nt.id, 0, &fields(&[("Front", "front")])),
+            Err(AnkiError::TemplateError { .. })
+        ));
+
+        // cloze ordinals select the cloze to reveal
+        let nt = col.get_notetype_by_name("Cloze")?.unwrap();
+        let text = fields(&[("Text", "{{c1::one}} {{c2::two}}")]);
+        let out = col.render_card_for_fields(nt.id, 1, &text)?;
+        assert!(out.question().contains(r#"data-ordinal="1">one<"#));
+        assert!(out.question().contains(r#"data-ordinal="2">[...]"#));
+
+        Ok(())
+    }
 }
@@ -6,6 +6,8 @@
 use std::collections::HashMap;
 use std::mem;
 
+use itertools::Itertools;
+
 use super::CardGenContext;
 use super::CardTemplate;
 use super::Notetype;
@@ -61,6 +63,36 @@ impl TemplateOrdChanges {
 }
 
 impl Collection {
+    /// Move a notetype's fields so that `new_order[i]` is the old ordinal of
+    /// the field now at position `i`, and rewrite its notes to match. The sort
+    /// field follows the field it pointed to. Templates refer to fields by
+    /// name, so they are unaffected.
+    pub fn reorder_notetype_fields(
+        &mut self,
+        ntid: NotetypeId,
+        new_order: &[usize],
+    ) -> Result<OpOutput<()>> {
+        let mut nt = self
+            .get_notetype(ntid)?
+            .or_not_found(ntid)?
+            .as_ref()
+            .clone();
+        require!(
+            new_order
+                .iter()
+                .copied()
+                .sorted_unstable()
+                .eq(0..nt.fields.len()),
+            "new field order must contain each of the {} field ordinals once",
+            nt.fields.len()
+        );
+        nt.fields = new_order
+            .iter()
+            .map(|&ord| nt.fields[ord].clone())
+            .collect();
+        self.update_notetype(&mut nt, false)
+    }
+
     /// Rewrite notes to match the updated field schema.
     /// Caller must create transaction.
     pub(crate) fn update_notes_for_changed_fields(
@@ -281,6 +313,64 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn reordering_fields() -> Result<()> {
+        let mut col = Collection::new();
+        let mut nt = col.get_notetype_by_name("Basic")?.unwrap().as_ref().clone();
+        nt.add_field("Extra");
+        col.update_notetype(&mut nt, false)?;
+        let mut note = nt.new_note();
+        *note.fields_mut() = vec!["front".into(), "back".into(), "extra".into()];
+        col.add_note(&mut note, DeckId(1))?;
+
+        col.reorder_notetype_fields(nt.id, &[2, 0, 1])?;
+        let nt = col.get_notetype(nt.id)?.unwrap();
+        assert_eq!(
+            nt.fields
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Extra", "Front", "Back"]
+        );
+        assert_eq!(nt.config.sort_field_idx, 1);
+        let note = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(note.fields(), &["extra", "front", "back"]);
+        assert_eq!(col.storage.all_cards_of_note(note.id)?.len(), 1);
+
+        for invalid in [&[0, 1][..], &[0, 0, 1], &[0, 1, 3], &[0, 1, 2, 3]] {
+            assert!(col.reorder_notetype_fields(nt.id, invalid).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn changing_sort_field_updates_notes() -> Result<()> {
+        let mut col = Collection::new();
+        let mut nt = col.get_notetype_by_name("Basic")?.unwrap().as_ref().clone();
+        let mut note = nt.new_note();
+        *note.fields_mut() = vec!["front".into(), "<b>back</b>".into()];
+        col.add_note(&mut note, DeckId(1))?;
+        let sort_field = |col: &mut Collection| {
+            col.storage
+                .get_note(note.id)
+                .unwrap()
+                .unwrap()
+                .sort_field
+                .unwrap()
+        };
+        assert_eq!(sort_field(&mut col), "front");
+
+        nt.config.sort_field_idx = 1;
+        col.update_notetype(&mut nt, false)?;
+        assert_eq!(sort_field(&mut col), "back");
+
+        col.undo()?;
+        assert_eq!(sort_field(&mut col), "front");
+
+        Ok(())
+    }
+
     #[test]
     fn field_renaming_and_deleting() -> Result<()> {
         let mut col = Collection::new();
@@ -3,6 +3,7 @@
 
 use super::CardTemplateConfig;
 use super::CardTemplateProto;
+use crate::error::CardTypeErrorDetails;
 use crate::prelude::*;
 use crate::template::ParsedTemplate;
 
@@ -24,6 +25,20 @@ impl CardTemplate {
         ParsedTemplate::from_text(&self.config.a_format).ok()
     }
 
+    /// The first side of this template that fails to parse, and why.
+    pub(crate) fn parse_error(&self) -> Option<CardTypeErrorDetails> {
+        [
+            (true, &self.config.q_format),
+            (false, &self.config.a_format),
+        ]
+        .into_iter()
+        .find_map(|(question, text)| {
+            ParsedTemplate::from_text(text)
+                .err()
+                .map(|error| CardTypeErrorDetails::TemplateParseError { question, error })
+        })
+    }
+
     pub(crate) fn question_format_for_browser(&self) -> &str {
         if !self.config.q_format_browser.is_empty() {
             &self.config.q_format_browser
@@ -19,6 +19,7 @@ pub enum Op {
     FindAndReplace,
     ImageOcclusion,
     Import,
+    MergeDecks,
     RebuildFilteredDeck,
     RemoveDeck,
     RemoveNote,
@@ -79,6 +80,7 @@ impl Op {
             Op::RemoveTag => tr.actions_remove_tag(),
             Op::ReparentTag => tr.actions_rename_tag(),
             Op::ReparentDeck => tr.actions_rename_deck(),
+            Op::MergeDecks => tr.actions_merge_decks(),
             Op::BuildFilteredDeck => tr.actions_build_filtered_deck(),
             Op::RebuildFilteredDeck => tr.actions_build_filtered_deck(),
             Op::EmptyFilteredDeck => tr.studying_empty(),
@@ -86,6 +86,13 @@ impl RevlogEntry {
 }
 
 impl Collection {
+    /// All review log entries of the given card, oldest first.
+    pub fn card_revlog(&self, cid: CardId) -> Result<Vec<RevlogEntry>> {
+        let mut entries = self.storage.get_revlog_entries_for_card(cid)?;
+        entries.sort_unstable_by_key(|entry| entry.id);
+        Ok(entries)
+    }
+
     pub(crate) fn log_manually_scheduled_review(
         &mut self,
         card: &Card,
@@ -112,3 +119,31 @@ impl Collection {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::NoteAdder;
+
+    #[test]
+    fn card_revlog() -> Result<()> {
+        let mut col = Collection::new();
+        NoteAdder::basic(&mut col).add(&mut col);
+        let cid = col.answer_good().card_id;
+        col.set_due_date(&[cid], "1", None)?;
+
+        let revlog = col.card_revlog(cid)?;
+        assert_eq!(
+            revlog
+                .iter()
+                .map(|entry| (entry.review_kind, entry.button_chosen))
+                .collect::<Vec<_>>(),
+            [
+                (RevlogReviewKind::Learning, 3),
+                (RevlogReviewKind::Manual, 0)
+            ]
+        );
+        assert_eq!(revlog[1].interval, 1);
+        Ok(())
+    }
+}
@@ -76,7 +76,7 @@ impl CardStateUpdater {
 
     /// Adds secs + fuzz to current time
     pub(super) fn fuzzed_next_learning_timestamp(&self, secs: u32) -> i32 {
-        TimestampSecs::now().0 as i32 + self.learning_ivl_with_fuzz(self.fuzz_seed, secs) as i32
+        self.now.0 as i32 + self.learning_ivl_with_fuzz(self.fuzz_seed, secs) as i32
     }
 
     /// Add up to 25% increase to seconds, but no more than 5 minutes.
@@ -8,8 +8,11 @@ mod relearning;
 mod review;
 mod revlog;
 
+use std::collections::HashMap;
+
 use fsrs::NextStates;
 use fsrs::FSRS;
+use itertools::Itertools;
 use rand::prelude::*;
 use rand::rngs::StdRng;
 use revlog::RevlogEntryPartial;
@@ -54,7 +57,7 @@ pub struct CardAnswer {
 
 impl CardAnswer {
     fn cap_answer_secs(&mut self, max_secs: u32) {
-        self.milliseconds_taken = self.milliseconds_taken.min(max_secs * 1000);
+        self.milliseconds_taken = self.milliseconds_taken.min(max_secs.saturating_mul(1000));
     }
 }
 
@@ -71,6 +74,8 @@ struct CardStateUpdater {
     fsrs_next_states: Option<NextStates>,
     /// Set if FSRS is enabled.
     desired_retention: Option<f32>,
+    /// Set if the load balancer is enabled.
+    review_load: Option<HashMap<u32, usize>>,
 }
 
 impl CardStateUpdater {
@@ -80,7 +85,9 @@ impl CardStateUpdater {
     pub(crate) fn state_context(&self) -> StateContext<'_> {
         StateContext {
             fuzz_factor: get_fuzz_factor(self.fuzz_seed),
+            review_load: self.review_load.as_ref(),
             steps: self.learn_steps(),
+            learning_step_fuzz: self.config.inner.learning_step_fuzz,
             graduating_interval_good: self.config.inner.graduating_interval_good,
             graduating_interval_easy: self.config.inner.graduating_interval_easy,
             initial_ease_factor: self.config.inner.initial_ease,
@@ -106,6 +113,22 @@ impl CardStateUpdater {
         }
     }
 
+    /// The range of days the card's next review intervals may fall on,
+    /// including the days the load balancer may move them to. None if no
+    /// answer leads to a review.
+    fn review_interval_window(&self) -> Option<(u32, u32)> {
+        let states = self.current_card_state().next_states(&self.state_context());
+        let (min_ivl, max_ivl) = [states.again, states.hard, states.good, states.easy]
+            .into_iter()
+            .filter_map(|state| state.review_state())
+            .map(|state| state.scheduled_days)
+            .minmax()
+            .into_option()?;
+        // balancing may shift an interval by a day, and Easy's interval also
+        // depends on Good's
+        Some((min_ivl.saturating_sub(2), max_ivl + 2))
+    }
+
     fn learn_steps(&self) -> LearningSteps<'_> {
         LearningSteps::new(&self.config.inner.learn_steps)
     }
@@ -221,6 +244,15 @@ impl Collection {
         Ok(current.next_states(&state_ctx))
     }
 
+    /// Return the states the card would move to if answered with
+    /// Again/Hard/Good/Easy, without modifying it. As the fuzz seed is derived
+    /// from the card's id and review count, any fuzz included in the returned
+    /// states is the same fuzz that answering the card would apply.
+    pub fn next_card_states(&mut self, cid: CardId) -> Result<[CardState; 4]> {
+        let states = self.get_scheduling_states(cid)?;
+        Ok([states.again, states.hard, states.good, states.easy])
+    }
+
     /// Describe the next intervals, to display on the answer buttons.
     pub fn describe_next_states(&mut self, choices: &SchedulingStates) -> Result<Vec<String>> {
         let collapse_time = self.learn_ahead_secs();
@@ -274,15 +306,84 @@ impl Collection {
         self.transact(Op::AnswerCard, |col| col.answer_card_inner(answer))
     }
 
+    /// Answer the provided cards in order, as if each had been answered at its
+    /// `answered_at` time, in a single undoable operation. The provided
+    /// current and new states are ignored; they are derived from the rating
+    /// and the card's state at the time of each answer.
+    pub fn answer_cards(&mut self, answers: &[CardAnswer]) -> Result<OpOutput<()>> {
+        for pair in answers.windows(2) {
+            if pair[1].answered_at < pair[0].answered_at {
+                invalid_input!("answers must be in chronological order");
+            }
+        }
+        self.transact(Op::AnswerCard, |col| {
+            for answer in answers {
+                col.replay_answer(answer)?;
+            }
+            col.clear_study_queues();
+            Ok(())
+        })
+    }
+
+    fn replay_answer(&mut self, answer: &CardAnswer) -> Result<()> {
+        let card = self
+            .storage
+            .get_card(answer.card_id)?
+            .or_not_found(answer.card_id)?;
+        let original = card.clone();
+        let now = answer.answered_at.as_secs();
+        let timing = self.timing_for_timestamp(now)?;
+        let updater = self.card_state_updater_at(card, timing, now)?;
+        let states = updater
+            .current_card_state()
+            .next_states(&updater.state_context());
+        let mut answer = CardAnswer {
+            card_id: answer.card_id,
+            current_state: states.current,
+            new_state: match answer.rating {
+                Rating::Again => states.again,
+                Rating::Hard => states.hard,
+                Rating::Good => states.good,
+                Rating::Easy => states.easy,
+            },
+            rating: answer.rating,
+            answered_at: answer.answered_at,
+            milliseconds_taken: answer.milliseconds_taken,
+            custom_data: answer.custom_data.clone(),
+        };
+        self.apply_answer(&mut answer, original, updater)?;
+        Ok(())
+    }
+
     fn answer_card_inner(&mut self, answer: &mut CardAnswer) -> Result<()> {
         let card = self
             .storage
             .get_card(answer.card_id)?
             .or_not_found(answer.card_id)?;
         let original = card.clone();
+        let updater = self.card_state_updater(card)?;
+        let timing = updater.timing;
+        let card = self.apply_answer(answer, original, updater)?;
+
+        self.update_queues_after_answering_card(
+            &card,
+            timing,
+            matches!(
+                answer.new_state,
+                CardState::Filtered(FilteredState::Preview(PreviewState { finished: true, .. }))
+            ),
+        )
+    }
+
+    /// Write the answer to the database, returning the updated card.
+    fn apply_answer(
+        &mut self,
+        answer: &mut CardAnswer,
+        original: Card,
+        mut updater: CardStateUpdater,
+    ) -> Result<Card> {
         let usn = self.usn()?;
 
-        let mut updater = self.card_state_updater(card)?;
         answer.cap_answer_secs(updater.config.inner.cap_answer_time_to_secs);
         let current_state = updater.current_card_state();
         // If the states aren't equal, it's probably because some time has passed.
@@ -299,7 +400,6 @@ impl Collection {
 
         self.update_deck_stats_from_answer(usn, answer, &updater, original.queue)?;
         self.maybe_bury_siblings(&original, &updater.config)?;
-        let timing = updater.timing;
         let mut card = updater.into_card();
         if let Some(data) = answer.custom_data.take() {
             card.custom_data = data;
@@ -310,14 +410,7 @@ impl Collection {
             self.add_leech_tag(card.note_id)?;
         }
 
-        self.update_queues_after_answering_card(
-            &card,
-            timing,
-            matches!(
-                answer.new_state,
-                CardState::Filtered(FilteredState::Preview(PreviewState { finished: true, .. }))
-            ),
-        )
+        Ok(card)
     }
 
     fn maybe_bury_siblings(&mut self, card: &Card, config: &DeckConfig) -> Result<()> {
@@ -352,6 +445,11 @@ impl Collection {
         updater: &CardStateUpdater,
         from_queue: CardQueue,
     ) -> Result<()> {
+        // stats only cover the current day, and updating them for a replayed
+        // answer from another day would reset today's counts
+        if updater.timing.days_elapsed != self.timing_today()?.days_elapsed {
+            return Ok(());
+        }
         let mut new_delta = 0;
         let mut review_delta = 0;
         match from_queue {
@@ -371,8 +469,17 @@ impl Collection {
         )
     }
 
-    fn card_state_updater(&mut self, mut card: Card) -> Result<CardStateUpdater> {
+    fn card_state_updater(&mut self, card: Card) -> Result<CardStateUpdater> {
         let timing = self.timing_today()?;
+        self.card_state_updater_at(card, timing, TimestampSecs::now())
+    }
+
+    fn card_state_updater_at(
+        &mut self,
+        mut card: Card,
+        timing: SchedTimingToday,
+        now: TimestampSecs,
+    ) -> Result<CardStateUpdater> {
         let deck = self
             .storage
             .get_deck(card.deck_id)?
@@ -409,16 +516,27 @@ impl Collection {
             None
         };
         let desired_retention = fsrs_enabled.then_some(config.inner.desired_retention);
-        Ok(CardStateUpdater {
+        let mut updater = CardStateUpdater {
             fuzz_seed: get_fuzz_seed(&card, false),
             card,
             deck,
             config,
             timing,
-            now: TimestampSecs::now(),
+            now,
             fsrs_next_states,
             desired_retention,
-        })
+            review_load: None,
+        };
+        if updater.config.inner.enable_load_balancer {
+            if let Some((min_ivl, max_ivl)) = updater.review_interval_window() {
+                updater.review_load = Some(self.storage.review_card_counts_by_day(
+                    timing.days_elapsed,
+                    min_ivl,
+                    max_ivl,
+                )?);
+            }
+        }
+        Ok(updater)
     }
 
     pub(crate) fn home_deck_config(
@@ -694,6 +812,196 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn next_card_states_match_answer() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut note = nt.new_note();
+        col.add_note(&mut note, DeckId(1))?;
+        let cid = col.search_cards("", SortMode::NoOrder)?[0];
+
+        let preview = col.next_card_states(cid)?;
+        // previewing doesn't touch the card
+        assert_eq!(col.next_card_states(cid)?, preview);
+        assert_eq!(col.storage.get_card(cid)?.unwrap().reps, 0);
+
+        let post_answer = col.answer_good();
+        assert_eq!(post_answer.new_state, preview[2]);
+
+        // with the load balancer, the previewed interval is already shifted
+        // away from the busy day
+        col.update_default_deck_config(|config| {
+            config.learn_steps.clear();
+            config.graduating_interval_good = 10;
+            config.enable_load_balancer = true;
+        });
+        CardAdder::new().due_dates(["10"]).add(&mut col);
+        let mut note = nt.new_note();
+        col.add_note(&mut note, DeckId(1))?;
+        let cid = col.storage.card_ids_of_notes(&[note.id])?[0];
+        let preview = col.next_card_states(cid)?;
+        assert_eq!(
+            preview[2].review_state().map(|state| state.scheduled_days),
+            Some(9)
+        );
+        let states = col.get_scheduling_states(cid)?;
+        col.answer_card(&mut CardAnswer {
+            card_id: cid,
+            current_state: states.current,
+            new_state: states.good,
+            rating: Rating::Good,
+            answered_at: TimestampMillis::now(),
+            milliseconds_taken: 0,
+            custom_data: None,
+        })?;
+        assert_eq!(current_state(&mut col, cid), preview[2]);
+        assert_eq!(
+            col.storage.get_card(cid)?.unwrap().due,
+            col.timing_today()?.days_elapsed as i32 + 9
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn answer_cards_replays_in_order() -> Result<()> {
+        let (mut col, cids) = v3_test_collection(1)?;
+        let cid = cids[0];
+        let start = TimestampMillis::now().0 - 120_000;
+        let replay = |offset: i64| CardAnswer {
+            card_id: cid,
+            // ignored; recomputed at answer time
+            current_state: CardState::Normal(NormalState::New(Default::default())),
+            new_state: CardState::Normal(NormalState::New(Default::default())),
+            rating: Rating::Good,
+            answered_at: TimestampMillis(start + offset),
+            milliseconds_taken: 1000,
+            custom_data: None,
+        };
+
+        // out-of-order answers are rejected without writing anything
+        assert!(col.answer_cards(&[replay(60_000), replay(0)]).is_err());
+        assert!(col.storage.get_revlog_entries_for_card(cid)?.is_empty());
+
+        // two good answers take the card through both learning steps
+        col.answer_cards(&[replay(0), replay(60_000)])?;
+        let card = col.storage.get_card(cid)?.unwrap();
+        assert_eq!(card.queue, CardQueue::Review);
+        assert_eq!(card.reps, 2);
+        let revlog_ids = col
+            .storage
+            .get_revlog_entries_for_card(cid)?
+            .into_iter()
+            .map(|e| e.id.0)
+            .collect::<Vec<_>>();
+        assert_eq!(revlog_ids, [start, start + 60_000]);
+
+        // a single undo step reverts the whole batch
+        col.undo()?;
+        assert_eq!(col.storage.get_card(cid)?.unwrap().reps, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn replaying_past_answers_keeps_todays_stats() -> Result<()> {
+        let (mut col, cids) = v3_test_collection(2)?;
+        let answered = col.answer_good().card_id;
+        let cid = *cids.iter().find(|cid| **cid != answered).unwrap();
+        col.answer_cards(&[CardAnswer {
+            card_id: cid,
+            current_state: CardState::Normal(NormalState::New(Default::default())),
+            new_state: CardState::Normal(NormalState::New(Default::default())),
+            rating: Rating::Good,
+            answered_at: TimestampMillis(TimestampMillis::now().0 - 86_400_000),
+            milliseconds_taken: 1000,
+            custom_data: None,
+        }])?;
+
+        assert_eq!(col.storage.get_card(cid)?.unwrap().reps, 1);
+        let deck = col.storage.get_deck(DeckId(1))?.unwrap();
+        assert_eq!(deck.common.new_studied, 1);
+        assert_eq!(
+            deck.common.last_day_studied,
+            col.timing_today()?.days_elapsed
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn answer_time_is_capped_to_preset() -> Result<()> {
+        let (mut col, _) = v3_test_collection(1)?;
+        col.update_default_deck_config(|config| config.cap_answer_time_to_secs = 30);
+        let queued = col.get_next_card()?.unwrap();
+        let mut answer = CardAnswer {
+            card_id: queued.card.id,
+            current_state: queued.states.current,
+            new_state: queued.states.good,
+            rating: Rating::Good,
+            answered_at: TimestampMillis::now(),
+            // two hours
+            milliseconds_taken: 7_200_000,
+            custom_data: None,
+        };
+        col.answer_card(&mut answer)?;
+
+        assert_eq!(answer.milliseconds_taken, 30_000);
+        let revlog = col.storage.get_revlog_entries_for_card(queued.card.id)?;
+        assert_eq!(revlog[0].taken_millis, 30_000);
+        let deck = col.storage.get_deck(DeckId(1))?.unwrap();
+        assert_eq!(deck.common.milliseconds_studied, 30_000);
+
+        Ok(())
+    }
+
+    fn max_cards_due_on_one_day(load_balancer: bool) -> Result<usize> {
+        let (mut col, _) = v3_test_collection(100)?;
+        col.update_default_deck_config(|config| {
+            config.learn_steps.clear();
+            config.new_per_day = 100;
+            config.graduating_interval_good = 10;
+            config.enable_load_balancer = load_balancer;
+        });
+        for _ in 0..100 {
+            col.answer_good();
+        }
+        let today = col.timing_today()?.days_elapsed;
+        let load = col.storage.review_card_counts_by_day(today, 0, 36500)?;
+        Ok(load.into_values().max().unwrap_or_default())
+    }
+
+    #[test]
+    fn load_balancer_spreads_due_dates() -> Result<()> {
+        assert_eq!(max_cards_due_on_one_day(false)?, 100);
+        assert!(max_cards_due_on_one_day(true)? <= 34);
+        Ok(())
+    }
+
+    #[test]
+    fn fuzzed_learning_steps_are_logged() -> Result<()> {
+        let (mut col, cids) = v3_test_collection(10)?;
+        col.update_default_deck_config(|config| {
+            // Good moves new cards on to the 20 day step
+            config.learn_steps = vec![1.0, 20.0 * 1440.0];
+            config.learning_step_fuzz = 20.0;
+        });
+        for _ in 0..10 {
+            col.answer_good();
+        }
+
+        let today = col.timing_today()?.days_elapsed as i32;
+        for cid in cids {
+            let card = col.storage.get_card(cid)?.unwrap();
+            let revlog = col.storage.get_revlog_entries_for_card(cid)?;
+            assert_eq!(card.queue, CardQueue::DayLearn);
+            assert_eq!(revlog[0].interval, card.due - today);
+            assert!((16..=24).contains(&revlog[0].interval));
+        }
+
+        Ok(())
+    }
+
     fn v3_test_collection(cards: usize) -> Result<(Collection, Vec<CardId>)> {
         let mut col = Collection::new();
         let nt = col.get_notetype_by_name("Basic")?.unwrap();
@@ -13,6 +13,14 @@ use crate::search::JoinSearches;
 use crate::search::SearchNode;
 use crate::search::StateKind;
 
+/// How many of the provided cards were (un)suspended, and how many were
+/// already in the desired state.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct SuspendResult {
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
 impl Card {
     /// True if card was buried/suspended prior to the call.
     pub(crate) fn restore_queue_after_bury_or_suspend(&mut self) -> bool {
@@ -129,6 +137,52 @@ impl Collection {
         })
     }
 
+    pub fn suspend_cards(&mut self, cids: &[CardId]) -> Result<OpOutput<SuspendResult>> {
+        self.transact(Op::Suspend, |col| {
+            let cards = col.all_cards_for_ids(cids, false)?;
+            let total = cards.len();
+            let changed = col.bury_or_suspend_cards_inner(cards, BuryOrSuspendMode::Suspend)?;
+            Ok(SuspendResult {
+                changed,
+                unchanged: total - changed,
+            })
+        })
+    }
+
+    /// Unsuspend the provided cards. Buried cards are left alone, and counted
+    /// as unchanged.
+    pub fn unsuspend_cards(&mut self, cids: &[CardId]) -> Result<OpOutput<SuspendResult>> {
+        self.transact(Op::UnburyUnsuspend, |col| {
+            let usn = col.usn()?;
+            let mut result = SuspendResult::default();
+            for original in col.all_cards_for_ids(cids, false)? {
+                if original.queue == CardQueue::Suspended {
+                    let mut card = original.clone();
+                    card.restore_queue_from_type();
+                    col.update_card_inner(&mut card, original, usn)?;
+                    result.changed += 1;
+                } else {
+                    result.unchanged += 1;
+                }
+            }
+            Ok(result)
+        })
+    }
+
+    /// Bury all cards of the provided note, leaving suspended cards alone.
+    /// `mode` determines whether they are buried manually or by the
+    /// scheduler. Returns the number of cards that were buried.
+    pub fn bury_note(&mut self, nid: NoteId, mode: BuryOrSuspendMode) -> Result<OpOutput<usize>> {
+        require!(
+            mode != BuryOrSuspendMode::Suspend,
+            "notes can only be buried"
+        );
+        self.transact(Op::Bury, |col| {
+            let cards = col.storage.all_cards_of_note(nid)?;
+            col.bury_or_suspend_cards_inner(cards, mode)
+        })
+    }
+
     pub(crate) fn bury_siblings(
         &mut self,
         card: &Card,
@@ -166,9 +220,12 @@ impl CardQueue {
 
 #[cfg(test)]
 mod test {
+    use super::BuryOrSuspendMode;
+    use super::SuspendResult;
     use crate::card::Card;
     use crate::card::CardQueue;
     use crate::collection::Collection;
+    use crate::prelude::*;
     use crate::search::SortMode;
     use crate::search::StateKind;
 
@@ -201,4 +258,59 @@ mod test {
         col.unbury_if_day_rolled_over(timing).unwrap();
         assert_count(&mut col, 0);
     }
+
+    #[test]
+    fn bury_note() -> Result<()> {
+        let mut col = Collection::new();
+        let note = NoteAdder::new(&col.basic_rev_notetype())
+            .fields(&["front", "back"])
+            .add(&mut col);
+        let cids = col.storage.card_ids_of_notes(&[note.id])?;
+        col.bury_or_suspend_cards(&cids[..1], BuryOrSuspendMode::Suspend)?;
+
+        // suspended cards are skipped
+        assert_eq!(
+            col.bury_note(note.id, BuryOrSuspendMode::BuryUser)?.output,
+            1
+        );
+        let queues: Vec<_> = col
+            .storage
+            .all_cards_of_note(note.id)?
+            .into_iter()
+            .map(|card| card.queue)
+            .collect();
+        assert!(queues.contains(&CardQueue::Suspended));
+        assert!(queues.contains(&CardQueue::UserBuried));
+
+        assert!(col.bury_note(note.id, BuryOrSuspendMode::Suspend).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn suspend_counts() -> Result<()> {
+        let mut col = Collection::new();
+        let note = NoteAdder::new(&col.basic_rev_notetype())
+            .fields(&["front", "back"])
+            .add(&mut col);
+        let cids = col.storage.card_ids_of_notes(&[note.id])?;
+        let result = |changed, unchanged| SuspendResult { changed, unchanged };
+
+        assert_eq!(col.suspend_cards(&cids[..1])?.output, result(1, 0));
+        assert_eq!(col.suspend_cards(&cids)?.output, result(1, 1));
+        assert_eq!(col.unsuspend_cards(&cids[..1])?.output, result(1, 0));
+
+        // buried cards are not unsuspended
+        col.bury_or_suspend_cards(&cids[..1], BuryOrSuspendMode::BuryUser)?;
+        assert_eq!(col.unsuspend_cards(&cids)?.output, result(1, 1));
+        let queues: Vec<_> = col
+            .storage
+            .all_cards_of_note(note.id)?
+            .into_iter()
+            .map(|card| card.queue)
+            .collect();
+        assert_eq!(queues, [CardQueue::UserBuried, CardQueue::New]);
+
+        Ok(())
+    }
 }
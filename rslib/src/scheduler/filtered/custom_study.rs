@@ -212,6 +212,7 @@ fn custom_study_config(
         preview_again_secs: 60,
         preview_hard_secs: 600,
         preview_good_secs: 0,
+        preserve_manual_order: false,
     }
 }
 
@@ -4,6 +4,8 @@
 mod card;
 mod custom_study;
 
+use std::collections::HashMap;
+
 use crate::config::ConfigKey;
 use crate::config::SchedulerVersion;
 use crate::decks::FilteredDeck;
@@ -96,17 +98,66 @@ impl Collection {
         Ok(())
     }
 
-    fn build_filtered_deck(&mut self, ctx: DeckFilterContext) -> Result<usize> {
+    fn build_filtered_deck(
+        &mut self,
+        ctx: DeckFilterContext,
+        previous_positions: &HashMap<CardId, i32>,
+    ) -> Result<usize> {
         let start = -100_000;
         let mut position = start;
         let fsrs = self.get_config_bool(BoolKey::Fsrs);
         for term in ctx.config.search_terms.iter().take(2) {
             position = self.move_cards_matching_term(&ctx, term, position, fsrs)?;
         }
+        if !previous_positions.is_empty() {
+            self.restore_previous_positions(&ctx, previous_positions, start)?;
+        }
 
         Ok((position - start) as usize)
     }
 
+    /// Positions of the cards in a filtered deck that were assigned one when
+    /// they were moved in.
+    fn filtered_card_positions(&mut self, did: DeckId) -> Result<HashMap<CardId, i32>> {
+        Ok(self
+            .storage
+            .all_cards_in_single_deck_full(did)?
+            .into_iter()
+            .filter(|card| card.original_due > 0)
+            .map(|card| (card.id, card.due))
+            .collect())
+    }
+
+    /// Renumber the positioned cards of a freshly-built deck, so that cards
+    /// which were there before come first in their old order, followed by
+    /// newly-gathered cards in the order they were gathered.
+    fn restore_previous_positions(
+        &mut self,
+        ctx: &DeckFilterContext,
+        previous_positions: &HashMap<CardId, i32>,
+        start: i32,
+    ) -> Result<()> {
+        let mut cards: Vec<_> = self
+            .storage
+            .all_cards_in_single_deck_full(ctx.target_deck)?
+            .into_iter()
+            .filter(|card| card.original_due > 0)
+            .collect();
+        cards.sort_by_key(|card| match previous_positions.get(&card.id) {
+            Some(position) => (false, *position),
+            None => (true, card.due),
+        });
+        for (position, mut card) in (start..).zip(cards) {
+            if card.due != position {
+                let original = card.clone();
+                card.due = position;
+                self.update_card_inner(&mut card, original, ctx.usn)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Move matching cards into filtered deck.
     /// Returns the new starting position.
     fn move_cards_matching_term(
@@ -195,8 +246,13 @@ impl Collection {
             timing,
         };
 
+        let previous_positions = if config.preserve_manual_order {
+            self.filtered_card_positions(deck.id)?
+        } else {
+            HashMap::new()
+        };
         self.return_all_cards_in_filtered_deck(deck.id)?;
-        self.build_filtered_deck(ctx)
+        self.build_filtered_deck(ctx, &previous_positions)
     }
 
     fn new_filtered_deck_for_adding(&mut self) -> Result<Deck> {
@@ -251,3 +307,111 @@ fn apply_update_to_filtered_deck(deck: &mut Deck, update: FilteredDeckForUpdate)
     deck.name = NativeDeckName::from_human_name(&update.human_name);
     deck.kind = DeckKind::Filtered(update.config);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::CardQueue;
+    use crate::card::CardType;
+    use crate::decks::FilteredSearchOrder;
+    use crate::tests::NoteAdder;
+
+    impl Collection {
+        fn card_ids_in_due_order(&mut self, did: DeckId) -> Vec<CardId> {
+            let mut cards = self.storage.all_cards_in_single_deck_full(did).unwrap();
+            cards.sort_by_key(|card| card.due);
+            cards.into_iter().map(|card| card.id).collect()
+        }
+    }
+
+    #[test]
+    fn rebuild_may_preserve_manual_order() -> Result<()> {
+        let mut col = Collection::new();
+        let mut cids = vec![];
+        for _ in 0..3 {
+            let note = NoteAdder::basic(&mut col).add(&mut col);
+            cids.push(col.storage.card_ids_of_notes(&[note.id])?[0]);
+        }
+        let mut deck = Deck::new_filtered();
+        let config = deck.filtered_mut()?;
+        config.preserve_manual_order = true;
+        config.search_terms.truncate(1);
+        config.search_terms[0].order = FilteredSearchOrder::Added as i32;
+        col.add_or_update_deck(&mut deck)?;
+        col.rebuild_filtered_deck(deck.id)?;
+        assert_eq!(col.card_ids_in_due_order(deck.id), cids);
+
+        // move the last card to the front
+        let mut card = col.storage.get_card(cids[2])?.unwrap();
+        card.due = -200_000;
+        col.storage.update_card(&card)?;
+        // and add another card, which should be placed after the existing ones
+        let note = NoteAdder::basic(&mut col).add(&mut col);
+        cids.push(col.storage.card_ids_of_notes(&[note.id])?[0]);
+
+        col.rebuild_filtered_deck(deck.id)?;
+        assert_eq!(
+            col.card_ids_in_due_order(deck.id),
+            [cids[2], cids[0], cids[1], cids[3]]
+        );
+
+        // without the option, the gather order is used
+        deck.filtered_mut()?.preserve_manual_order = false;
+        col.add_or_update_deck(&mut deck)?;
+        col.rebuild_filtered_deck(deck.id)?;
+        assert_eq!(col.card_ids_in_due_order(deck.id), cids);
+
+        Ok(())
+    }
+
+    #[test]
+    fn searches_are_ordered_and_limited_independently() -> Result<()> {
+        let mut col = Collection::new();
+        // move creation time back so cards can be overdue
+        let mut stamp = col.storage.creation_stamp()?;
+        stamp.0 -= 86_400 * 100;
+        col.set_creation_stamp(stamp)?;
+        let today = col.timing_today()?.days_elapsed as i32;
+
+        let mut reviews = vec![];
+        for days_overdue in [1, 5, 3, 4] {
+            let note = NoteAdder::basic(&mut col).add(&mut col);
+            let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+            card.ctype = CardType::Review;
+            card.queue = CardQueue::Review;
+            card.interval = 1;
+            card.due = today - days_overdue;
+            col.storage.update_card(&card)?;
+            reviews.push(card.id);
+        }
+        let mut new = vec![];
+        for _ in 0..3 {
+            let note = NoteAdder::basic(&mut col).add(&mut col);
+            new.push(col.storage.card_ids_of_notes(&[note.id])?[0]);
+        }
+
+        let mut deck = Deck::new_filtered();
+        deck.filtered_mut()?.search_terms = vec![
+            FilteredSearchTerm {
+                search: "is:due".into(),
+                limit: 3,
+                order: FilteredSearchOrder::Due as i32,
+            },
+            FilteredSearchTerm {
+                search: "is:new".into(),
+                limit: 2,
+                order: FilteredSearchOrder::Added as i32,
+            },
+        ];
+        col.add_or_update_deck(&mut deck)?;
+        assert_eq!(col.rebuild_filtered_deck(deck.id)?.output, 5);
+
+        // most overdue reviews first, followed by the oldest new cards
+        assert_eq!(
+            col.card_ids_in_due_order(deck.id),
+            [reviews[1], reviews[3], reviews[2], new[0], new[1]]
+        );
+
+        Ok(())
+    }
+}
@@ -49,12 +49,14 @@ impl Collection {
     /// Should be called inside a transaction.
     /// If Weights are None, it means the user disabled FSRS, and the existing
     /// memory state should be removed.
+    /// Returns the number of cards that were rescheduled.
     pub(crate) fn update_memory_state(
         &mut self,
         entries: Vec<UpdateMemoryStateEntry>,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let timing = self.timing_today()?;
         let usn = self.usn()?;
+        let mut rescheduled = 0;
         for UpdateMemoryStateEntry {
             req,
             search,
@@ -120,6 +122,7 @@ impl Collection {
                                         };
                                         *due = (timing.days_elapsed as i32) - days_elapsed
                                             + card.interval as i32;
+                                        rescheduled += 1;
                                         // Add a manual revlog entry if the last entry wasn't manual
                                         if !last_info.last_revlog_is_manual {
                                             self.log_manually_scheduled_review(
@@ -140,7 +143,42 @@ impl Collection {
                 self.update_card_inner(&mut card, original, usn)?;
             }
         }
-        Ok(())
+        Ok(rescheduled)
+    }
+
+    /// Recalculate the memory state of review cards matching `search` with
+    /// the FSRS weights of their presets, and reschedule them to match each
+    /// preset's desired retention. New and learning cards are left alone.
+    /// Returns the number of cards that were rescheduled.
+    pub fn reschedule_cards_with_fsrs(&mut self, search: &str) -> Result<OpOutput<usize>> {
+        require!(self.get_config_bool(BoolKey::Fsrs), "FSRS is not enabled");
+        let search = search.try_into_search()?;
+        self.transact(Op::SetDueDate, |col| {
+            let entries = col
+                .storage
+                .all_deck_config()?
+                .into_iter()
+                .map(|config| {
+                    Ok(UpdateMemoryStateEntry {
+                        search: SearchBuilder::all([
+                            search.clone(),
+                            SearchNode::PresetId(config.id).into(),
+                            SearchNode::State(StateKind::Review).into(),
+                        ])
+                        .try_into_search()?,
+                        ignore_before: ignore_revlogs_before_ms_from_config(&config)?,
+                        req: Some(UpdateMemoryStateRequest {
+                            weights: config.inner.fsrs_weights,
+                            desired_retention: config.inner.desired_retention,
+                            historical_retention: config.inner.historical_retention,
+                            max_interval: config.inner.maximum_review_interval,
+                            reschedule: true,
+                        }),
+                    })
+                })
+                .collect::<Result<_>>()?;
+            col.update_memory_state(entries)
+        })
     }
 
     pub fn compute_memory_state(&mut self, card_id: CardId) -> Result<ComputeMemoryStateResponse> {
@@ -335,6 +373,7 @@ mod tests {
     use fsrs::MemoryState;
 
     use super::*;
+    use crate::card::CardQueue;
     use crate::card::FsrsMemoryState;
     use crate::revlog::RevlogReviewKind;
     use crate::scheduler::fsrs::weights::tests::convert;
@@ -442,4 +481,49 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn rescheduling_with_fsrs() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_rev_notetype();
+        NoteAdder::new(&nt).fields(&["front", "back"]).add(&mut col);
+        let mut cards = col.all_cards_for_search("")?;
+        cards.sort_by_key(|c| c.id);
+        // rescheduling requires FSRS to be enabled
+        assert!(col.reschedule_cards_with_fsrs("").is_err());
+        col.set_config_bool(BoolKey::Fsrs, true, false)?;
+
+        // only the first card is in review; the second one is new
+        let mut card = cards[0].clone();
+        card.ctype = CardType::Review;
+        card.queue = CardQueue::Review;
+        card.interval = 1000;
+        card.due = 1000;
+        col.storage.update_card(&card)?;
+        for (kind, days_ago) in [
+            (RevlogReviewKind::Learning, 20),
+            (RevlogReviewKind::Review, 10),
+        ] {
+            col.storage.add_revlog_entry(
+                &RevlogEntry {
+                    cid: card.id,
+                    ..revlog(kind, days_ago)
+                },
+                true,
+            )?;
+        }
+
+        assert_eq!(col.reschedule_cards_with_fsrs("")?.output, 1);
+        let card = col.storage.get_card(card.id)?.unwrap();
+        assert!(card.memory_state.is_some());
+        assert_ne!(card.interval, 1000);
+        let new_card = col.storage.get_card(cards[1].id)?.unwrap();
+        assert_eq!(new_card, cards[1]);
+
+        col.undo()?;
+        let card = col.storage.get_card(card.id)?.unwrap();
+        assert_eq!(card.interval, 1000);
+        assert_eq!(card.memory_state, None);
+        Ok(())
+    }
 }
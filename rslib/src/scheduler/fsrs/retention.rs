@@ -10,6 +10,7 @@ use itertools::Itertools;
 use crate::prelude::*;
 use crate::revlog::RevlogEntry;
 use crate::revlog::RevlogReviewKind;
+use crate::search::SearchNode;
 use crate::search::SortMode;
 
 #[derive(Default, Clone, Copy, Debug)]
@@ -75,6 +76,86 @@ impl Collection {
             .min(0.95) as f32)
     }
 
+    /// Simulate a year of study of `deck_size` cards with the provided
+    /// weights and the limits of the given preset, and return the desired
+    /// retention that yields the most knowledge within a daily budget of
+    /// `minutes_per_day`. Review costs and rating probabilities come from the
+    /// preset's review history, falling back on FSRS's defaults when too few
+    /// reviews are available. Nothing is saved.
+    pub fn compute_optimal_retention_for_budget(
+        &mut self,
+        config_id: DeckConfigId,
+        params: &[f32],
+        deck_size: u32,
+        minutes_per_day: u32,
+    ) -> Result<f32> {
+        require!(deck_size > 0, "deck size must be positive");
+        require!(minutes_per_day > 0, "time budget must be positive");
+        let config = self
+            .get_deck_config(config_id, false)?
+            .or_not_found(config_id)?;
+        let mut anki_progress = self.new_progress_handler::<ComputeRetentionProgress>();
+        let revlogs = self
+            .search_cards_into_table(SearchNode::PresetId(config_id), SortMode::NoOrder)?
+            .col
+            .storage
+            .get_revlog_entries_for_searched_cards_in_card_order()?;
+        let defaults = SimulatorConfig::default();
+        let (recall_costs, forget_cost, learn_cost, first_rating_prob, review_rating_prob) =
+            match self.get_optimal_retention_parameters(revlogs) {
+                Ok(p) => (
+                    [p.recall_secs_hard, p.recall_secs_good, p.recall_secs_easy],
+                    p.forget_secs,
+                    p.learn_secs,
+                    [
+                        p.first_rating_probability_again,
+                        p.first_rating_probability_hard,
+                        p.first_rating_probability_good,
+                        p.first_rating_probability_easy,
+                    ],
+                    [
+                        p.review_rating_probability_hard,
+                        p.review_rating_probability_good,
+                        p.review_rating_probability_easy,
+                    ],
+                ),
+                Err(
+                    AnkiError::FsrsInsufficientData | AnkiError::FsrsInsufficientReviews { .. },
+                ) => (
+                    defaults.recall_costs,
+                    defaults.forget_cost,
+                    defaults.learn_cost,
+                    defaults.first_rating_prob,
+                    defaults.review_rating_prob,
+                ),
+                Err(err) => return Err(err),
+            };
+        let simulator_config = SimulatorConfig {
+            deck_size: deck_size as usize,
+            learn_span: 365,
+            max_cost_perday: minutes_per_day as f64 * 60.0,
+            max_ivl: config.inner.maximum_review_interval as f64,
+            recall_costs,
+            forget_cost,
+            learn_cost,
+            first_rating_prob,
+            review_rating_prob,
+            learn_limit: config.inner.new_per_day as usize,
+            review_limit: config.inner.reviews_per_day as usize,
+            ..defaults
+        };
+        Ok(FSRS::new(None)?
+            .optimal_retention(&simulator_config, params, |ip| {
+                anki_progress
+                    .update(false, |p| {
+                        p.current = ip.current as u32;
+                    })
+                    .is_ok()
+            })?
+            .max(0.75)
+            .min(0.95) as f32)
+    }
+
     pub fn get_optimal_retention_parameters(
         &mut self,
         revlogs: Vec<RevlogEntry>,
@@ -236,3 +317,31 @@ fn median_secs(group: &Vec<u32>) -> f64 {
         0.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fsrs::DEFAULT_PARAMETERS;
+
+    use super::*;
+
+    #[test]
+    fn optimal_retention_for_budget() -> Result<()> {
+        let mut col = Collection::new();
+        let retention = col.compute_optimal_retention_for_budget(
+            DeckConfigId(1),
+            &DEFAULT_PARAMETERS,
+            100,
+            10,
+        )?;
+        assert!(retention > 0.7 && retention < 0.99, "{retention}");
+
+        assert!(col
+            .compute_optimal_retention_for_budget(DeckConfigId(1), &DEFAULT_PARAMETERS, 100, 0)
+            .is_err());
+        assert!(col
+            .compute_optimal_retention_for_budget(DeckConfigId(123), &DEFAULT_PARAMETERS, 100, 10)
+            .is_err());
+
+        Ok(())
+    }
+}
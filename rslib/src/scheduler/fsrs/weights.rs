@@ -109,6 +109,51 @@ impl Collection {
         })
     }
 
+    /// Fit FSRS weights to the review history of cards matching `search`,
+    /// without saving them. `progress` is regularly called with a value
+    /// between 0.0 and 1.0. The optimization can be cancelled with the
+    /// standard progress abort flag, and fails if there is not enough
+    /// history to train on.
+    pub fn optimize_fsrs_parameters(
+        &mut self,
+        search: &str,
+        mut progress: impl FnMut(f32),
+    ) -> Result<Weights> {
+        let mut anki_progress = self.new_progress_handler::<ComputeWeightsProgress>();
+        let timing = self.timing_today()?;
+        let revlogs = self.revlog_for_srs(search)?;
+        let (items, review_count) =
+            fsrs_items_for_training(revlogs, timing.next_day_at, TimestampMillis(0));
+        if items.is_empty() {
+            return Err(AnkiError::FsrsInsufficientData);
+        }
+        anki_progress.state.reviews = review_count as u32;
+
+        let training_progress = CombinedProgressState::new_shared();
+        let weights = thread::scope(|scope| {
+            let training_progress2 = training_progress.clone();
+            let handle = scope.spawn(move || {
+                FSRS::new(None)?.compute_parameters(items, Some(training_progress2))
+            });
+            while !handle.is_finished() {
+                thread::sleep(Duration::from_millis(100));
+                let mut guard = training_progress.lock().unwrap();
+                let (current, total) = (guard.current(), guard.total());
+                progress(current as f32 / total.max(1) as f32);
+                if let Err(_err) = anki_progress.update(false, |p| {
+                    p.current_iteration = current as u32;
+                    p.total_iterations = total as u32;
+                }) {
+                    guard.want_abort = true;
+                }
+            }
+            handle.join().unwrap()
+        })?;
+        progress(1.0);
+
+        Ok(weights)
+    }
+
     pub(crate) fn revlog_for_srs(
         &mut self,
         search: impl TryIntoSearch,
@@ -431,6 +476,18 @@ pub(crate) mod tests {
 
     pub(crate) use fsrs_items;
 
+    #[test]
+    fn optimizing_requires_history() {
+        let mut col = Collection::new();
+        NoteAdder::basic(&mut col).add(&mut col);
+        let mut calls = 0;
+        assert_eq!(
+            col.optimize_fsrs_parameters("", |_| calls += 1),
+            Err(AnkiError::FsrsInsufficientData)
+        );
+        assert_eq!(calls, 0);
+    }
+
     #[test]
     fn delta_t_is_correct() -> Result<()> {
         assert_eq!(
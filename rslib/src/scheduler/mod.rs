@@ -54,6 +54,19 @@ impl Collection {
         Ok(((self.timing_today()?.days_elapsed as i32) + delta).max(0) as u32)
     }
 
+    /// Number of review cards in the deck that will be due `day_offset` days
+    /// from today. Learning cards are not included.
+    pub fn cards_due_on_day(
+        &mut self,
+        did: DeckId,
+        include_subdecks: bool,
+        day_offset: u32,
+    ) -> Result<usize> {
+        let dids = self.deck_ids_maybe_with_children(did, include_subdecks)?;
+        let day = self.timing_today()?.days_elapsed + day_offset;
+        self.storage.review_cards_due_on_day_in_decks(&dids, day)
+    }
+
     pub(crate) fn timing_for_timestamp(&mut self, now: TimestampSecs) -> Result<SchedTimingToday> {
         let current_utc_offset = self.local_utc_offset_for_user()?;
 
@@ -120,6 +133,25 @@ impl Collection {
         }
     }
 
+    /// The hour of the day (0-23) at which a new scheduling day starts.
+    pub fn rollover_hour(&self) -> Result<u8> {
+        self.rollover_for_current_scheduler()
+    }
+
+    /// Change the hour at which a new scheduling day starts. Cards are not
+    /// rescheduled; only the cutoff used for subsequent scheduling changes,
+    /// so cards may become due earlier or later today than they otherwise
+    /// would have.
+    pub fn set_rollover_hour(&mut self, hour: u8) -> Result<OpOutput<()>> {
+        require!(hour < 24, "invalid rollover hour: {hour}");
+        self.transact(Op::UpdateConfig, |col| {
+            col.set_rollover_for_current_scheduler(hour)?;
+            // recalculate the cutoff now, so it's consistent for the rest of
+            // the session
+            col.timing_today().map(|_| ())
+        })
+    }
+
     pub(crate) fn set_rollover_for_current_scheduler(&mut self, hour: u8) -> Result<()> {
         match self.scheduler_version() {
             SchedulerVersion::V1 => Err(AnkiError::SchedulerUpgradeRequired),
@@ -132,3 +164,78 @@ impl Collection {
         self.storage.set_creation_stamp(stamp)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::CardQueue;
+    use crate::tests::CardAdder;
+    use crate::tests::DeckAdder;
+
+    #[test]
+    fn rollover_hour() -> Result<()> {
+        let mut col = Collection::new();
+        assert_eq!(col.rollover_hour()?, 4);
+        assert!(col.set_rollover_hour(24).is_err());
+
+        let next_day_at = col.timing_today()?.next_day_at;
+        col.set_rollover_hour(5)?;
+        assert_eq!(col.rollover_hour()?, 5);
+        // the cutoff moves by an hour, unless the new cutoff has already
+        // passed today
+        let delta = col
+            .timing_today()?
+            .next_day_at
+            .elapsed_secs_since(next_day_at);
+        assert_eq!(delta.rem_euclid(86_400), 3600);
+
+        col.undo()?;
+        assert_eq!(col.rollover_hour()?, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cards_due_on_day() -> Result<()> {
+        let mut col = Collection::new();
+        let parent = DeckAdder::new("parent").add(&mut col);
+        let child = DeckAdder::new("parent::child").add(&mut col);
+        CardAdder::new()
+            .siblings(3)
+            .deck(parent.id)
+            .due_dates(["1", "1", "2"])
+            .add(&mut col);
+        CardAdder::new()
+            .deck(child.id)
+            .due_dates(["1"])
+            .add(&mut col);
+
+        assert_eq!(col.cards_due_on_day(parent.id, false, 1)?, 2);
+        assert_eq!(col.cards_due_on_day(parent.id, true, 1)?, 3);
+        assert_eq!(col.cards_due_on_day(parent.id, true, 2)?, 1);
+        assert_eq!(col.cards_due_on_day(parent.id, true, 0)?, 0);
+        assert!(col.cards_due_on_day(DeckId(123), true, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn new_cards_in_filtered_decks_are_not_due() -> Result<()> {
+        let mut col = Collection::new();
+        NoteAdder::basic(&mut col).add(&mut col);
+        let mut card = col.get_first_card();
+        card.due = col.timing_today()?.days_elapsed as i32 + 1;
+        col.storage.update_card(&card)?;
+
+        // without rescheduling, the new card moves into the review queue,
+        // keeping its position in odue
+        let mut filtered_deck = Deck::new_filtered();
+        filtered_deck.filtered_mut()?.reschedule = false;
+        col.add_or_update_deck(&mut filtered_deck)?;
+        col.rebuild_filtered_deck(filtered_deck.id)?;
+        assert_eq!(col.get_first_card().queue, CardQueue::Review);
+        assert_eq!(col.cards_due_on_day(filtered_deck.id, false, 1)?, 0);
+
+        Ok(())
+    }
+}
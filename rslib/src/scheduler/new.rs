@@ -190,6 +190,20 @@ impl Collection {
         })
     }
 
+    /// Reset the provided cards to new, leaving their review history intact.
+    /// A manual entry is added to the review log, so statistics and FSRS
+    /// can tell where the card was reset. If `restore_position` is set, cards
+    /// return to their original position in the new queue when it is known.
+    /// `reset_counts` zeroes the cards' review and lapse counts.
+    pub fn forget_cards(
+        &mut self,
+        cids: &[CardId],
+        restore_position: bool,
+        reset_counts: bool,
+    ) -> Result<OpOutput<()>> {
+        self.reschedule_cards_as_new(cids, true, restore_position, reset_counts, None)
+    }
+
     pub fn reschedule_cards_as_new_defaults(
         &self,
         context: ScheduleAsNewContext,
@@ -253,6 +267,26 @@ impl Collection {
         Ok(count)
     }
 
+    /// Move a single new card to `position`. If `shift_others` is set, new
+    /// cards at or after that position are moved back by one first.
+    pub fn set_new_card_position(
+        &mut self,
+        cid: CardId,
+        position: u32,
+        shift_others: bool,
+    ) -> Result<OpOutput<()>> {
+        self.transact(Op::SortCards, |col| {
+            let usn = col.usn()?;
+            if shift_others {
+                col.shift_existing_cards(position, 1, usn)?;
+            }
+            let mut card = col.storage.get_card(cid)?.or_not_found(cid)?;
+            let original = card.clone();
+            require!(card.set_new_position(position), "card {cid} is not new");
+            col.update_card_inner(&mut card, original, usn)
+        })
+    }
+
     pub fn reposition_defaults(&self) -> RepositionDefaultsResponse {
         RepositionDefaultsResponse {
             random: self.get_config_bool(BoolKey::RandomOrderReposition),
@@ -311,6 +345,8 @@ impl From<NewCardInsertOrder> for NewCardDueOrder {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::revlog::RevlogReviewKind;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn new_order() {
@@ -346,6 +382,74 @@ mod test {
         unreachable!("not random");
     }
 
+    #[test]
+    fn set_new_card_position() -> Result<()> {
+        let mut col = Collection::new();
+        let cids: Vec<_> = (0..3)
+            .map(|_| {
+                let note = NoteAdder::basic(&mut col).add(&mut col);
+                col.storage.card_ids_of_notes(&[note.id]).unwrap()[0]
+            })
+            .collect();
+        let positions = |col: &Collection| -> Vec<i32> {
+            cids.iter()
+                .map(|cid| col.storage.get_card(*cid).unwrap().unwrap().due)
+                .collect()
+        };
+        assert_eq!(positions(&col), [1, 2, 3]);
+
+        col.set_new_card_position(cids[2], 1, true)?;
+        assert_eq!(positions(&col), [2, 3, 1]);
+        col.set_new_card_position(cids[0], 10, false)?;
+        assert_eq!(positions(&col), [10, 3, 1]);
+
+        // only new cards can be positioned
+        col.answer_good();
+        assert!(col.set_new_card_position(cids[2], 5, true).is_err());
+        assert_eq!(positions(&col)[..2], [10, 3]);
+        assert!(col.set_new_card_position(CardId(1), 5, false).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn forget_cards() -> Result<()> {
+        let mut col = Collection::new();
+        let note = NoteAdder::basic(&mut col).add(&mut col);
+        NoteAdder::basic(&mut col).add(&mut col);
+        let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+        col.answer_good();
+        card.ctype = CardType::Review;
+        card.queue = CardQueue::Review;
+        card.due = 100;
+        card.interval = 10;
+        (card.reps, card.lapses) = (5, 1);
+        card.original_position = Some(1);
+        col.storage.update_card(&card)?;
+
+        col.forget_cards(&[card.id], true, false)?;
+        let card = col.storage.get_card(card.id)?.unwrap();
+        assert_eq!(
+            (card.queue, card.ctype, card.due, card.reps, card.lapses),
+            (CardQueue::New, CardType::New, 1, 5, 1)
+        );
+        // the earlier review is kept, and the reset is logged
+        let revlog = col.storage.get_revlog_entries_for_card(card.id)?;
+        assert_eq!(revlog.len(), 2);
+        assert_eq!(revlog[1].review_kind, RevlogReviewKind::Manual);
+
+        // without restoring the position, the card goes to the end of the queue
+        col.forget_cards(&[card.id], false, true)?;
+        let card = col.storage.get_card(card.id)?.unwrap();
+        assert_eq!((card.due, card.reps, card.lapses), (3, 0, 0));
+
+        // each call is a single undo step
+        col.undo()?;
+        assert_eq!(col.storage.get_card(card.id)?.unwrap().due, 1);
+
+        Ok(())
+    }
+
     #[test]
     fn last_position() {
         // new card
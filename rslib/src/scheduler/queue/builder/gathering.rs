@@ -5,6 +5,7 @@ use super::DueCard;
 use super::NewCard;
 use super::QueueBuilder;
 use crate::deckconfig::NewCardGatherPriority;
+use crate::deckconfig::NewCardSortOrder;
 use crate::decks::limits::LimitKind;
 use crate::prelude::*;
 use crate::scheduler::queue::DueCardKind;
@@ -16,6 +17,9 @@ impl QueueBuilder {
         self.gather_due_cards(col, DueCardKind::Learning)?;
         self.gather_due_cards(col, DueCardKind::Review)?;
         self.gather_new_cards(col)?;
+        if self.context.sort_options.new_order == NewCardSortOrder::Field {
+            self.fetch_new_card_field_values(col)?;
+        }
 
         Ok(())
     }
@@ -55,6 +55,8 @@ pub(crate) struct NewCard {
     pub original_deck_id: DeckId,
     pub template_index: u32,
     pub hash: u64,
+    /// Only set for [NewCardSortOrder::Field].
+    pub field_value: Option<f64>,
 }
 
 impl From<DueCard> for MainQueueEntry {
@@ -93,6 +95,7 @@ impl From<DueCard> for LearningQueueEntry {
 #[derive(Default, Clone, Debug)]
 pub(super) struct QueueSortOptions {
     pub(super) new_order: NewCardSortOrder,
+    pub(super) new_sort_field: String,
     pub(super) new_gather_priority: NewCardGatherPriority,
     pub(super) review_order: ReviewCardOrder,
     pub(super) day_learn_mix: ReviewMix,
@@ -211,6 +214,7 @@ fn sort_options(deck: &Deck, config_map: &HashMap<DeckConfigId, DeckConfig>) ->
         .and_then(|config_id| config_map.get(&config_id))
         .map(|config| QueueSortOptions {
             new_order: config.inner.new_card_sort_order(),
+            new_sort_field: config.inner.new_card_sort_field.clone(),
             new_gather_priority: config.inner.new_card_gather_priority(),
             review_order: config.inner.review_order(),
             day_learn_mix: config.inner.interday_learning_mix(),
@@ -281,6 +285,8 @@ mod test {
     use super::*;
     use crate::card::CardQueue;
     use crate::card::CardType;
+    use crate::deckconfig::LimitOverride;
+    use crate::scheduler::queue::QueueEntryKind;
 
     impl Collection {
         fn set_deck_gather_order(&mut self, deck: &mut Deck, order: NewCardGatherPriority) {
@@ -498,6 +504,23 @@ mod test {
         assert_eq!(col.card_queue_len(), 1);
     }
 
+    #[test]
+    fn deck_config_may_let_new_cards_ignore_review_limit() {
+        let mut col = Collection::new();
+        col.update_default_deck_config(|config| {
+            config.reviews_per_day = 1;
+        });
+        CardAdder::new().siblings(2).due_dates(["0"]).add(&mut col);
+        CardAdder::new().add(&mut col);
+        // review limit caps the new card as well
+        assert_eq!(col.card_queue_len(), 1);
+
+        col.update_default_deck_config(|config| {
+            config.new_ignore_review_limit = true;
+        });
+        assert_eq!(col.card_queue_len(), 2);
+    }
+
     #[test]
     fn reviews_dont_affect_new_limit_before_review_limit_is_reached() {
         let mut col = Collection::new();
@@ -523,4 +546,101 @@ mod test {
         col.set_current_deck(child.id).unwrap();
         assert_eq!(col.card_queue_len(), 0);
     }
+
+    #[test]
+    fn limit_override_applies_until_expiry() -> Result<()> {
+        let mut col = Collection::new();
+        let today = col.timing_today()?.days_elapsed;
+        col.update_default_deck_config(|config| {
+            config.new_per_day = 0;
+            config.limit_override = Some(LimitOverride {
+                new_limit: 5,
+                review_limit: 200,
+                expires_on_day: today + 1,
+            });
+        });
+        CardAdder::new().siblings(2).add(&mut col);
+        assert_eq!(col.card_queue_len(), 2);
+
+        // move to the day after the override expires
+        let crt = col.storage.creation_stamp()?;
+        col.set_creation_stamp(crt.adding_secs(-86_400 * 2))?;
+        col.clear_study_queues();
+        assert_eq!(col.timing_today()?.days_elapsed, today + 2);
+        assert_eq!(col.card_queue_len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn new_cards_can_be_sorted_by_field() -> Result<()> {
+        let mut col = Collection::new();
+        col.update_default_deck_config(|config| {
+            config.new_card_sort_order = NewCardSortOrder::Field as i32;
+            config.new_card_sort_field = "Back".to_string();
+        });
+        for (front, back) in [
+            ("a", "3"),
+            ("b", "n/a"),
+            ("c", "<b>-1</b>"),
+            ("d", "2.5"),
+            ("e", ""),
+        ] {
+            NoteAdder::basic(&mut col)
+                .fields(&[front, back])
+                .add(&mut col);
+        }
+        let fronts: Vec<_> = col
+            .build_queues(DeckId(1))?
+            .iter()
+            .map(|entry| {
+                let card = col.storage.get_card(entry.card_id()).unwrap().unwrap();
+                let note = col.storage.get_note(card.note_id).unwrap().unwrap();
+                note.fields()[0].clone()
+            })
+            .collect();
+        // non-numeric values come last, in gather order
+        assert_eq!(fronts, ["c", "d", "a", "b", "e"]);
+        Ok(())
+    }
+
+    #[test]
+    fn interday_learning_cards_are_due_after_rollover() -> Result<()> {
+        let mut col = Collection::new();
+        col.set_default_learn_steps(vec![1440.0, 2880.0]);
+        NoteAdder::basic(&mut col).add(&mut col);
+        let card_id = col.answer_again().card_id;
+        let card = col.storage.get_card(card_id)?.unwrap();
+        assert_eq!(card.queue, CardQueue::DayLearn);
+        assert!(col.get_next_card()?.is_none());
+
+        // move to the next day
+        let mut stamp = col.storage.creation_stamp()?;
+        stamp.0 -= 86_400;
+        col.set_creation_stamp(stamp)?;
+        let queued = col.get_queued_cards(1, false)?;
+        assert_eq!(queued.learning_count, 1);
+        assert_eq!(queued.cards[0].card.id, card_id);
+        assert_eq!(queued.cards[0].kind, QueueEntryKind::Learning);
+        Ok(())
+    }
+
+    #[test]
+    fn learning_cards_due_on_a_past_day_are_gathered() -> Result<()> {
+        let mut col = Collection::new();
+        NoteAdder::basic(&mut col).add(&mut col);
+        let card_id = col.answer_again().card_id;
+        let mut card = col.storage.get_card(card_id)?.unwrap();
+        assert_eq!(card.queue, CardQueue::Learn);
+        // the step ended two days ago, but the card wasn't studied since
+        card.due = TimestampSecs::now().adding_secs(-2 * 86_400).0 as i32;
+        col.storage.update_card(&card)?;
+        col.clear_study_queues();
+
+        let queued = col.get_queued_cards(1, false)?;
+        assert_eq!(queued.learning_count, 1);
+        assert_eq!(queued.cards[0].card.id, card_id);
+        assert_eq!(queued.cards[0].kind, QueueEntryKind::Learning);
+        Ok(())
+    }
 }
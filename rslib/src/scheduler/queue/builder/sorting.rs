@@ -2,6 +2,8 @@
 // License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
 
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::hash::Hasher;
 
 use fnv::FnvHasher;
@@ -9,6 +11,8 @@ use fnv::FnvHasher;
 use super::NewCard;
 use super::NewCardSortOrder;
 use super::QueueBuilder;
+use crate::prelude::*;
+use crate::text::strip_html;
 
 impl QueueBuilder {
     pub(super) fn sort_new(&mut self) {
@@ -32,7 +36,27 @@ impl QueueBuilder {
                 self.hash_new_cards_by_id();
                 self.new.sort_unstable_by(cmp_hash)
             }
+            NewCardSortOrder::Field => {
+                // stable sort to preserve gather order
+                self.new.sort_by(cmp_field_value)
+            }
+        }
+    }
+
+    /// Store the numeric value of the configured sort field on each gathered
+    /// new card.
+    pub(super) fn fetch_new_card_field_values(&mut self, col: &mut Collection) -> Result<()> {
+        let field_name = &self.context.sort_options.new_sort_field;
+        let mut note_values = HashMap::new();
+        for card in &mut self.new {
+            card.field_value = match note_values.entry(card.note_id) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    *entry.insert(numeric_field_value(col, card.note_id, field_name)?)
+                }
+            };
         }
+        Ok(())
     }
 
     fn hash_new_cards_by_id(&mut self) {
@@ -48,6 +72,28 @@ impl QueueBuilder {
     }
 }
 
+fn numeric_field_value(col: &mut Collection, nid: NoteId, field_name: &str) -> Result<Option<f64>> {
+    let note = col.storage.get_note(nid)?.or_not_found(nid)?;
+    let notetype = col
+        .get_notetype(note.notetype_id)?
+        .or_not_found(note.notetype_id)?;
+    Ok(notetype
+        .get_field_ord(field_name)
+        .and_then(|ord| note.fields().get(ord))
+        .and_then(|text| strip_html(text).trim().parse::<f64>().ok())
+        .filter(|value| value.is_finite()))
+}
+
+/// Cards without a value sort last.
+fn cmp_field_value(a: &NewCard, b: &NewCard) -> Ordering {
+    match (a.field_value, b.field_value) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
 fn cmp_hash(a: &NewCard, b: &NewCard) -> Ordering {
     a.hash.cmp(&b.hash)
 }
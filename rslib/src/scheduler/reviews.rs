@@ -55,6 +55,22 @@ impl Card {
             self.ease_factor = ease_factor;
         }
     }
+
+    /// Recompute the due date from the card's existing interval, treating it
+    /// as days from today. Learning cards are made due immediately.
+    /// New cards are left alone, and false is returned.
+    fn reschedule_from_interval(&mut self, today: u32, now: TimestampSecs) -> bool {
+        if self.ctype == CardType::New {
+            return false;
+        }
+        self.remove_from_filtered_deck_restoring_queue();
+        self.due = match self.ctype {
+            CardType::Review => (today + self.interval.max(1)) as i32,
+            _ if self.is_intraday_learning() => now.0 as i32,
+            _ => today as i32,
+        };
+        true
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -143,6 +159,27 @@ impl Collection {
             Ok(())
         })
     }
+
+    /// Recompute the due dates of imported cards from their stored
+    /// intervals, so they become due relative to the current day.
+    /// New cards are skipped.
+    pub fn reschedule_cards_as_new_from_intervals(
+        &mut self,
+        cids: &[CardId],
+    ) -> Result<OpOutput<()>> {
+        let usn = self.usn()?;
+        let today = self.timing_today()?.days_elapsed;
+        let now = TimestampSecs::now();
+        self.transact(Op::SetDueDate, |col| {
+            for mut card in col.all_cards_for_ids(cids, false)? {
+                let original = card.clone();
+                if card.reschedule_from_interval(today, now) {
+                    col.update_card_inner(&mut card, original, usn)?;
+                }
+            }
+            Ok(())
+        })
+    }
 }
 
 #[cfg(test)]
@@ -244,4 +281,92 @@ mod test {
         assert_eq!(c.interval, 2);
         assert_eq!(c.ease_factor, 2200); // interval doesn't change
     }
+
+    #[test]
+    fn reschedule_from_interval() {
+        let now = TimestampSecs(1_000_000_000);
+        let mut c = Card::new(NoteId(0), 0, DeckId(1), 3);
+
+        // new cards are skipped
+        assert!(!c.reschedule_from_interval(10, now));
+        assert_eq!(c.due, 3);
+
+        // review cards become due `interval` days from today
+        c.ctype = CardType::Review;
+        c.queue = CardQueue::Review;
+        c.interval = 5;
+        c.due = 123;
+        assert!(c.reschedule_from_interval(10, now));
+        assert_eq!(c.due, 15);
+        assert_eq!(c.interval, 5);
+
+        // intraday learning cards become due now
+        c.ctype = CardType::Relearn;
+        c.queue = CardQueue::Learn;
+        assert!(c.reschedule_from_interval(10, now));
+        assert_eq!(c.due, now.0 as i32);
+
+        // interday learning cards become due today
+        c.ctype = CardType::Learn;
+        c.queue = CardQueue::DayLearn;
+        assert!(c.reschedule_from_interval(10, now));
+        assert_eq!(c.due, 10);
+    }
+
+    #[test]
+    fn reschedule_cards_from_intervals() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut note = nt.new_note();
+        col.add_note(&mut note, DeckId(1))?;
+        let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+        card.ctype = CardType::Review;
+        card.queue = CardQueue::Review;
+        card.interval = 7;
+        card.due = 1000;
+        col.storage.update_card(&card)?;
+
+        col.reschedule_cards_as_new_from_intervals(&[card.id])?;
+        let today = col.timing_today()?.days_elapsed;
+        assert_eq!(
+            col.storage.get_card(card.id)?.unwrap().due,
+            (today + 7) as i32
+        );
+
+        // a single undo step restores the imported due date
+        col.undo()?;
+        assert_eq!(col.storage.get_card(card.id)?.unwrap().due, 1000);
+        Ok(())
+    }
+
+    #[test]
+    fn set_due_date_on_learning_card() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut note = nt.new_note();
+        col.add_note(&mut note, DeckId(1))?;
+        let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+        card.ctype = CardType::Learn;
+        card.queue = CardQueue::Learn;
+        card.due = 1_000_000_000;
+        col.storage.update_card(&card)?;
+
+        // invalid specs are reported
+        let err = col.set_due_date(&[card.id], "3-x", None).unwrap_err();
+        assert!(matches!(err, AnkiError::InvalidInput { .. }));
+        assert!(err.message(&col.tr).contains("3-x"));
+
+        col.set_due_date(&[card.id], "3-7", None)?;
+        let today = col.timing_today()?.days_elapsed as i32;
+        let updated = col.storage.get_card(card.id)?.unwrap();
+        assert_eq!(updated.ctype, CardType::Review);
+        assert_eq!(updated.queue, CardQueue::Review);
+        assert!((today + 3..=today + 7).contains(&updated.due));
+        assert_eq!(updated.interval as i32, updated.due - today);
+
+        // a single undo step restores the learning card
+        col.undo()?;
+        assert_eq!(col.storage.get_card(card.id)?.unwrap(), card);
+        Ok(())
+    }
 }
@@ -1,6 +1,8 @@
 // Copyright: Ankitects Pty Ltd and contributors
 // License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
 
+use std::collections::HashMap;
+
 use super::StateContext;
 use crate::collection::Collection;
 use crate::prelude::*;
@@ -34,8 +36,22 @@ static FUZZ_RANGES: [FuzzRange; 3] = [
 impl<'a> StateContext<'a> {
     /// Apply fuzz, respecting the passed bounds.
     /// Caller must ensure reasonable bounds.
+    /// If the load balancer is enabled, the result is then shifted by up to a
+    /// day towards the day with the fewest due reviews, without leaving the
+    /// interval's fuzz range.
     pub(crate) fn with_review_fuzz(&self, interval: f32, minimum: u32, maximum: u32) -> u32 {
-        with_review_fuzz(self.fuzz_factor, interval, minimum, maximum)
+        let fuzzed = with_review_fuzz(self.fuzz_factor, interval, minimum, maximum);
+        if let Some(review_load) = self.review_load {
+            let (lower, upper) = constrained_fuzz_bounds(interval, minimum, maximum);
+            load_balanced_interval(fuzzed, lower, upper, review_load)
+        } else {
+            fuzzed
+        }
+    }
+
+    /// Apply the preset's learning step fuzz to a (re)learning step.
+    pub(crate) fn with_learning_step_fuzz(&self, secs: u32) -> u32 {
+        with_learning_step_fuzz(self.fuzz_factor, secs, self.learning_step_fuzz)
     }
 }
 
@@ -58,6 +74,45 @@ impl Collection {
     }
 }
 
+/// Lengthen or shorten a learning step of more than a day by whole days, up to
+/// `percent` of its length but by at least a day, using `fuzz_factor` to pick
+/// the result. Shorter steps are returned unchanged.
+pub(crate) fn with_learning_step_fuzz(fuzz_factor: Option<f32>, secs: u32, percent: f32) -> u32 {
+    let days = secs / 86_400;
+    match fuzz_factor {
+        Some(fuzz_factor) if days > 1 && percent > 0.0 => {
+            let delta = ((days as f32 * percent / 100.0).round() as u32).max(1);
+            let lower = days.saturating_sub(delta).max(1);
+            let upper = days + delta;
+            let fuzzed = (lower as f32 + fuzz_factor * ((1 + upper - lower) as f32)).floor() as u32;
+            secs - days * 86_400 + fuzzed * 86_400
+        }
+        _ => secs,
+    }
+}
+
+/// Pick the interval within a day of `interval` and within `lower..=upper`
+/// that has the fewest reviews due, preferring `interval` in case of a tie.
+fn load_balanced_interval(
+    interval: u32,
+    lower: u32,
+    upper: u32,
+    review_load: &HashMap<u32, usize>,
+) -> u32 {
+    let load = |days| review_load.get(&days).copied().unwrap_or_default();
+    [interval.saturating_sub(1), interval + 1]
+        .into_iter()
+        .filter(|days| (lower..=upper).contains(days) && *days != interval)
+        .fold((interval, load(interval)), |best, days| {
+            if load(days) < best.1 {
+                (days, load(days))
+            } else {
+                best
+            }
+        })
+        .0
+}
+
 pub(crate) fn with_review_fuzz(
     fuzz_factor: Option<f32>,
     interval: f32,
@@ -170,6 +225,32 @@ mod test {
         assert_lower_middle_upper!(100.0, 97, 103, 97, 100, 103);
     }
 
+    #[test]
+    fn learning_step_fuzz() {
+        const DAY: u32 = 86_400;
+        // sub-day and single-day steps are never fuzzed
+        for fuzz_factor in [Some(0.0), Some(0.99)] {
+            assert_eq!(with_learning_step_fuzz(fuzz_factor, 600, 20.0), 600);
+            assert_eq!(with_learning_step_fuzz(fuzz_factor, DAY, 20.0), DAY);
+        }
+        // a 3-day step varies by a day in either direction
+        assert_eq!(with_learning_step_fuzz(Some(0.0), 3 * DAY, 20.0), 2 * DAY);
+        assert_eq!(with_learning_step_fuzz(Some(0.5), 3 * DAY, 20.0), 3 * DAY);
+        assert_eq!(with_learning_step_fuzz(Some(0.99), 3 * DAY, 20.0), 4 * DAY);
+        // longer steps vary by the given percentage, keeping any part day
+        assert_eq!(
+            with_learning_step_fuzz(Some(0.0), 20 * DAY + 600, 20.0),
+            16 * DAY + 600
+        );
+        assert_eq!(
+            with_learning_step_fuzz(Some(0.99), 20 * DAY, 20.0),
+            24 * DAY
+        );
+        // disabled
+        assert_eq!(with_learning_step_fuzz(Some(0.0), 3 * DAY, 0.0), 3 * DAY);
+        assert_eq!(with_learning_step_fuzz(None, 3 * DAY, 20.0), 3 * DAY);
+    }
+
     #[test]
     fn invalid_values_will_not_panic() {
         constrained_fuzz_bounds(1.0, 3, 2);
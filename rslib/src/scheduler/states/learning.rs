@@ -39,7 +39,7 @@ impl LearnState {
     fn answer_again(self, ctx: &StateContext) -> LearnState {
         LearnState {
             remaining_steps: ctx.steps.remaining_for_failed(),
-            scheduled_secs: ctx.steps.again_delay_secs_learn(),
+            scheduled_secs: ctx.with_learning_step_fuzz(ctx.steps.again_delay_secs_learn()),
             elapsed_secs: 0,
             memory_state: ctx.fsrs_next_states.as_ref().map(|s| s.again.memory.into()),
         }
@@ -47,11 +47,12 @@ impl LearnState {
 
     fn answer_hard(self, ctx: &StateContext) -> LearnState {
         LearnState {
-            scheduled_secs: ctx
-                .steps
-                .hard_delay_secs(self.remaining_steps)
-                // user has 0 learning steps, which the UI doesn't allow
-                .unwrap_or(60),
+            scheduled_secs: ctx.with_learning_step_fuzz(
+                ctx.steps
+                    .hard_delay_secs(self.remaining_steps)
+                    // user has 0 learning steps, which the UI doesn't allow
+                    .unwrap_or(60),
+            ),
             elapsed_secs: 0,
             memory_state: ctx.fsrs_next_states.as_ref().map(|s| s.hard.memory.into()),
             ..self
@@ -63,7 +64,7 @@ impl LearnState {
         if let Some(good_delay) = ctx.steps.good_delay_secs(self.remaining_steps) {
             LearnState {
                 remaining_steps: ctx.steps.remaining_for_good(self.remaining_steps),
-                scheduled_secs: good_delay,
+                scheduled_secs: ctx.with_learning_step_fuzz(good_delay),
                 elapsed_secs: 0,
                 memory_state,
             }
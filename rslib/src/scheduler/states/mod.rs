@@ -13,6 +13,8 @@ pub(crate) mod rescheduling_filter;
 pub(crate) mod review;
 pub(crate) mod steps;
 
+use std::collections::HashMap;
+
 pub use filtered::FilteredState;
 use fsrs::NextStates;
 pub(crate) use interval_kind::IntervalKind;
@@ -85,10 +87,15 @@ pub(crate) struct StateContext<'a> {
     /// In range `0.0..1.0`. Used to pick the final interval from the fuzz
     /// range.
     pub fuzz_factor: Option<f32>,
+    /// Set if the load balancer is enabled. The number of reviews due on each
+    /// upcoming day, keyed by days from today.
+    pub review_load: Option<&'a HashMap<u32, usize>>,
     pub fsrs_next_states: Option<NextStates>,
 
     // learning
     pub steps: LearningSteps<'a>,
+    /// Percentage by which (re)learning steps of more than a day are fuzzed.
+    pub learning_step_fuzz: f32,
     pub graduating_interval_good: u32,
     pub graduating_interval_easy: u32,
     pub initial_ease_factor: f32,
@@ -124,7 +131,9 @@ impl<'a> StateContext<'a> {
     pub(crate) fn defaults_for_testing() -> Self {
         Self {
             fuzz_factor: None,
+            review_load: None,
             steps: LearningSteps::new(&[1.0, 10.0]),
+            learning_step_fuzz: 0.0,
             graduating_interval_good: 1,
             graduating_interval_easy: 4,
             initial_ease_factor: 2.5,
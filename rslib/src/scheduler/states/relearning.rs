@@ -40,7 +40,7 @@ impl RelearnState {
             RelearnState {
                 learning: LearnState {
                     remaining_steps: ctx.relearn_steps.remaining_for_failed(),
-                    scheduled_secs: again_delay,
+                    scheduled_secs: ctx.with_learning_step_fuzz(again_delay),
                     elapsed_secs: 0,
                     memory_state,
                 },
@@ -65,7 +65,7 @@ impl RelearnState {
         {
             RelearnState {
                 learning: LearnState {
-                    scheduled_secs: hard_delay,
+                    scheduled_secs: ctx.with_learning_step_fuzz(hard_delay),
                     memory_state,
                     ..self.learning
                 },
@@ -89,7 +89,7 @@ impl RelearnState {
         {
             RelearnState {
                 learning: LearnState {
-                    scheduled_secs: good_delay,
+                    scheduled_secs: ctx.with_learning_step_fuzz(good_delay),
                     remaining_steps: ctx
                         .relearn_steps
                         .remaining_for_good(self.learning.remaining_steps),
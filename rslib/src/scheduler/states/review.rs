@@ -103,7 +103,7 @@ impl ReviewState {
             RelearnState {
                 learning: LearnState {
                     remaining_steps: ctx.relearn_steps.remaining_for_failed(),
-                    scheduled_secs: again_delay,
+                    scheduled_secs: ctx.with_learning_step_fuzz(again_delay),
                     elapsed_secs: 0,
                     memory_state,
                 },
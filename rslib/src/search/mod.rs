@@ -8,6 +8,7 @@ mod sqlwriter;
 pub(crate) mod writer;
 
 use std::borrow::Cow;
+use std::fmt::Write;
 
 pub use builder::JoinSearches;
 pub use builder::Negated;
@@ -26,6 +27,7 @@ use sqlwriter::SqlWriter;
 pub use writer::replace_search_node;
 
 use crate::browser_table::Column;
+use crate::card::CardQueue;
 use crate::card::CardType;
 use crate::prelude::*;
 use crate::scheduler::timing::SchedTimingToday;
@@ -43,6 +45,38 @@ pub enum SortMode {
     Custom(String),
 }
 
+/// The number of cards matching a search in each queue, ignoring daily limits.
+/// Cards in filtered decks are counted by their original type.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct QueueCounts {
+    pub new: usize,
+    /// Both intraday and interday learning cards.
+    pub learning: usize,
+    pub review: usize,
+    pub suspended: usize,
+    pub buried: usize,
+}
+
+impl QueueCounts {
+    fn add(&mut self, queue: CardQueue, ctype: CardType, filtered: bool, count: usize) {
+        let field = match queue {
+            CardQueue::Suspended => &mut self.suspended,
+            CardQueue::SchedBuried | CardQueue::UserBuried => &mut self.buried,
+            // cards in filtered decks may have been moved into the review or
+            // preview queue, but keep their original type
+            _ if filtered || queue == CardQueue::PreviewRepeat => match ctype {
+                CardType::New => &mut self.new,
+                CardType::Learn | CardType::Relearn => &mut self.learning,
+                CardType::Review => &mut self.review,
+            },
+            CardQueue::New => &mut self.new,
+            CardQueue::Learn | CardQueue::DayLearn => &mut self.learning,
+            CardQueue::Review | CardQueue::PreviewRepeat => &mut self.review,
+        };
+        *field += count;
+    }
+}
+
 pub trait AsReturnItemType {
     fn as_return_item_type() -> ReturnItemType;
 }
@@ -161,6 +195,23 @@ impl Collection {
         self.search(search, mode)
     }
 
+    /// Return up to `limit` matching cards, skipping the first `offset`, and
+    /// the total number of matching cards. Only the requested page is
+    /// fetched, so this is cheaper than [Collection::search_cards] when a
+    /// search matches many cards.
+    pub fn search_cards_paged<N>(
+        &mut self,
+        search: N,
+        mode: SortMode,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<CardId>, usize)>
+    where
+        N: TryIntoSearch,
+    {
+        self.search_paged(search, mode, offset, limit)
+    }
+
     pub fn search_notes<N>(&mut self, search: N, mode: SortMode) -> Result<Vec<NoteId>>
     where
         N: TryIntoSearch,
@@ -197,6 +248,40 @@ impl Collection {
         Ok(ids)
     }
 
+    fn search_paged<T, N>(
+        &mut self,
+        search: N,
+        mode: SortMode,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<T>, usize)>
+    where
+        N: TryIntoSearch,
+        T: FromSql + AsReturnItemType,
+    {
+        let item_type = T::as_return_item_type();
+        let top_node = search.try_into_search()?;
+        let writer = SqlWriter::new(self, item_type);
+
+        let (mut sql, args) = writer.build_query(&top_node, mode.required_table())?;
+        let count_sql = format!("select count(*) from ({sql})");
+        self.add_order(&mut sql, item_type, mode)?;
+        write!(sql, " limit {limit} offset {offset}").unwrap();
+
+        // slow searches can be cancelled by the frontend
+        self.storage.with_abort_handler(&self.state.progress, || {
+            let total: usize =
+                self.storage
+                    .db
+                    .query_row(&count_sql, params_from_iter(args.iter()), |row| row.get(0))?;
+            let mut stmt = self.storage.db.prepare(&sql)?;
+            let ids: Vec<_> = stmt
+                .query_map(params_from_iter(args.iter()), |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?;
+            Ok((ids, total))
+        })
+    }
+
     fn add_order(
         &mut self,
         sql: &mut String,
@@ -251,6 +336,20 @@ impl Collection {
         Ok(CardTableGuard { cards, col: self })
     }
 
+    pub fn search_card_count_by_queue(
+        &mut self,
+        search: impl TryIntoSearch,
+    ) -> Result<QueueCounts> {
+        let guard = self.search_cards_into_table(search, SortMode::NoOrder)?;
+        let mut counts = QueueCounts::default();
+        for (queue, ctype, filtered, count) in
+            guard.col.storage.searched_card_queue_and_type_counts()?
+        {
+            counts.add(queue, ctype, filtered, count);
+        }
+        Ok(counts)
+    }
+
     pub(crate) fn all_cards_for_search(&mut self, search: impl TryIntoSearch) -> Result<Vec<Card>> {
         let guard = self.search_cards_into_table(search, SortMode::NoOrder)?;
         guard.col.storage.all_searched_cards()
@@ -443,9 +542,12 @@ fn prepare_sort(col: &mut Collection, column: Column, item_type: ReturnItemType)
 #[cfg(test)]
 mod test {
     use anki_proto::search::browser_columns::Sorting;
+    use rusqlite::functions::FunctionFlags;
     use strum::IntoEnumIterator;
 
     use super::*;
+    use crate::tests::DeckAdder;
+    use crate::tests::NoteAdder;
 
     impl SchedTimingToday {
         pub(crate) fn zero() -> Self {
@@ -471,4 +573,363 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn sort_cards_by_note_creation() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut nids = vec![];
+        for _ in 0..3 {
+            let mut note = nt.new_note();
+            col.add_note(&mut note, DeckId(1))?;
+            nids.push(note.id);
+        }
+        // simulate the first note's card having been generated last
+        col.storage.db.execute(
+            "update cards set id = 1 + (select max(id) from cards) where nid = ?",
+            [nids[0]],
+        )?;
+        let card_nids = |col: &mut Collection, reverse| -> Result<Vec<NoteId>> {
+            let mode = SortMode::Builtin {
+                column: Column::NoteCreation,
+                reverse,
+            };
+            col.search_cards("", mode)?
+                .into_iter()
+                .map(|cid| Ok(col.storage.get_card(cid)?.unwrap().note_id))
+                .collect()
+        };
+
+        assert_eq!(card_nids(&mut col, false)?, nids);
+        nids.reverse();
+        assert_eq!(card_nids(&mut col, true)?, nids);
+        Ok(())
+    }
+
+    #[test]
+    fn search_by_lapses() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut cids = vec![];
+        for lapses in [0, 5, 6, 9] {
+            let mut note = nt.new_note();
+            col.add_note(&mut note, DeckId(1))?;
+            let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+            card.lapses = lapses;
+            col.storage.update_card(&card)?;
+            cids.push(card.id);
+        }
+
+        let mut found = col.search_cards("prop:lapses>5", SortMode::NoOrder)?;
+        found.sort();
+        assert_eq!(found, &cids[2..]);
+        assert_eq!(
+            col.search_cards("prop:lapses<1", SortMode::NoOrder)?,
+            &cids[..1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn search_by_card_count_of_note() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_rev_notetype();
+        let single = NoteAdder::new(&nt).fields(&["a", ""]).add(&mut col);
+        let double = NoteAdder::new(&nt).fields(&["b", "c"]).add(&mut col);
+
+        let search = |col: &mut Collection, search: &str| col.search_notes_unordered(search);
+        assert_eq!(search(&mut col, "prop:cards=1")?, [single.id]);
+        assert_eq!(search(&mut col, "prop:cards>1")?, [double.id]);
+        assert_eq!(search(&mut col, "prop:cards>=2")?, [double.id]);
+        assert_eq!(search(&mut col, "prop:cards<2")?, [single.id]);
+        assert_eq!(search(&mut col, "prop:cards<=2")?.len(), 2);
+        assert_eq!(
+            col.search_cards("prop:cards=2", SortMode::NoOrder)?.len(),
+            2
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_by_learning_step() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_notetype();
+        // default preset: 2 learning steps, 1 relearning step
+        let mut add_card = |ctype: CardType, remaining_steps: u32| -> Result<CardId> {
+            let note = NoteAdder::new(&nt).add(&mut col);
+            let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+            card.ctype = ctype;
+            card.remaining_steps = remaining_steps;
+            col.storage.update_card(&card)?;
+            Ok(card.id)
+        };
+        let first = add_card(CardType::Learn, 2)?;
+        let second = add_card(CardType::Learn, 1)?;
+        // the v2 scheduler also packs a count for today into `left`
+        let packed = add_card(CardType::Learn, 1002)?;
+        let relearning = add_card(CardType::Relearn, 1)?;
+        add_card(CardType::Review, 0)?;
+        add_card(CardType::New, 0)?;
+
+        let mut search = |search: &str| -> Result<Vec<CardId>> {
+            let mut cids = col.search_cards(search, SortMode::NoOrder)?;
+            cids.sort_unstable();
+            Ok(cids)
+        };
+        assert_eq!(search("prop:step=0")?, [first, packed, relearning]);
+        assert_eq!(search("prop:step=1")?, [second]);
+        assert_eq!(search("prop:step>0")?, [second]);
+        assert_eq!(search("prop:step<5")?.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_by_due_hours() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_notetype();
+        let now = TimestampSecs::now().0;
+        let mut add_card = |ctype: CardType, queue: CardQueue, due: i64| -> Result<CardId> {
+            let note = NoteAdder::new(&nt).add(&mut col);
+            let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+            card.ctype = ctype;
+            card.queue = queue;
+            card.due = due as i32;
+            col.storage.update_card(&card)?;
+            Ok(card.id)
+        };
+        let soon = add_card(CardType::Learn, CardQueue::Learn, now + 1800)?;
+        let later = add_card(CardType::Relearn, CardQueue::Learn, now + 5 * 3600 + 60)?;
+        // a day-based due number would look long overdue if read as a timestamp
+        add_card(CardType::Review, CardQueue::Review, 0)?;
+        add_card(CardType::Learn, CardQueue::DayLearn, 0)?;
+        // a previewed card in a filtered deck keeps its original position in
+        // odue
+        let preview = add_card(CardType::New, CardQueue::PreviewRepeat, now + 3 * 3600 + 60)?;
+        let filtered = DeckAdder::new("filtered").filtered(true).add(&mut col);
+        let mut card = col.storage.get_card(preview)?.unwrap();
+        card.original_deck_id = card.deck_id;
+        card.deck_id = filtered.id;
+        card.original_due = 5;
+        col.storage.update_card(&card)?;
+
+        assert_eq!(col.search_cards("prop:due-h<2", SortMode::NoOrder)?, [soon]);
+        assert_eq!(
+            col.search_cards("prop:due-h>=2", SortMode::Custom("c.id".into()))?,
+            [later, preview]
+        );
+        assert_eq!(
+            col.search_cards("prop:due-h=5", SortMode::NoOrder)?,
+            [later]
+        );
+        assert_eq!(
+            col.search_cards("prop:due-h=3", SortMode::NoOrder)?,
+            [preview]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_dupes_in_any_field() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut add = |front: &str, back: &str| -> Result<NoteId> {
+            let mut note = nt.new_note();
+            note.set_field(0, front)?;
+            note.set_field(1, back)?;
+            col.add_note(&mut note, DeckId(1))?;
+            Ok(note.id)
+        };
+        let a = add("1,x", "<b>key</b>")?;
+        let b = add("2", "key")?;
+        let c = add("key", "other")?;
+
+        let mut dupes = |search: String| -> Result<Vec<NoteId>> {
+            let mut nids = col.search_notes_unordered(search.as_str())?;
+            nids.sort();
+            Ok(nids)
+        };
+        assert_eq!(dupes(format!("dupe:{},1,key", nt.id))?, [a, b]);
+        // without an ordinal, the first field is used
+        assert_eq!(dupes(format!("dupe:{},key", nt.id))?, [c]);
+        assert_eq!(dupes(format!("dupe:{},0,1,x", nt.id))?, [a]);
+        assert!(dupes(format!("dupe:{},5,key", nt.id))?.is_empty());
+        // older searches for first fields that start with a number still work
+        // if the notetype has no field at that ordinal
+        let d = add("5,key", "")?;
+        assert_eq!(dupes(format!("dupe:{},5,key", nt.id))?, [d]);
+        assert!(dupes(format!("dupe:{},70000,key", nt.id))?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_edited_notes() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let today_start = col.timing_today()?.next_day_at.adding_secs(-86_400);
+        let mut nids = vec![];
+        for mtime in [
+            today_start.adding_secs(-1),
+            today_start,
+            today_start.adding_secs(1),
+        ] {
+            let mut note = nt.new_note();
+            col.add_note(&mut note, DeckId(1))?;
+            col.storage
+                .db
+                .execute("update notes set mod = ? where id = ?", (mtime, note.id))?;
+            nids.push(note.id);
+        }
+
+        // only notes modified after the start of today match
+        assert_eq!(col.search_notes_unordered("edited:1")?, &nids[2..]);
+        let mut found = col.search_notes_unordered("edited:2")?;
+        found.sort();
+        assert_eq!(found, nids);
+        // zero is treated the same as 1, like added:
+        assert_eq!(col.search_notes_unordered("edited:0")?, &nids[2..]);
+        assert!(col.search_notes_unordered("edited:-1").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn paged_card_search() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        for _ in 0..5 {
+            let mut note = nt.new_note();
+            col.add_note(&mut note, DeckId(1))?;
+        }
+        let mode = SortMode::Custom("c.id desc".into());
+        let all = col.search_cards("", mode.clone())?;
+
+        assert_eq!(
+            col.search_cards_paged("", mode.clone(), 1, 2)?,
+            (all[1..3].to_vec(), 5)
+        );
+        assert_eq!(
+            col.search_cards_paged("", mode.clone(), 4, 2)?,
+            (all[4..].to_vec(), 5)
+        );
+        assert_eq!(col.search_cards_paged("", mode, 5, 2)?, (vec![], 5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn paged_search_can_be_interrupted() -> Result<()> {
+        let mut col = Collection::new();
+        NoteAdder::basic(&mut col).add(&mut col);
+        // a stale abort request does not interrupt a new search, and
+        // unpaged searches leave it untouched
+        col.state.progress.lock().unwrap().want_abort = true;
+        assert_eq!(col.search_cards("", SortMode::NoOrder)?.len(), 1);
+        assert!(col.state.progress.lock().unwrap().want_abort);
+        assert_eq!(col.search_cards_paged("", SortMode::NoOrder, 0, 10)?.1, 1);
+
+        // an abort requested while the query runs interrupts it
+        let progress = col.state.progress.clone();
+        col.storage.db.create_scalar_function(
+            "request_abort",
+            0,
+            FunctionFlags::SQLITE_UTF8,
+            move |_ctx| {
+                progress.lock().unwrap().want_abort = true;
+                Ok(1)
+            },
+        )?;
+        let endless_order = SortMode::Custom(
+            concat!(
+                "(with recursive r(x) as (select request_abort() union all ",
+                "select x + 1 from r) select count(*) from r)"
+            )
+            .into(),
+        );
+        assert_eq!(
+            col.search_cards_paged("", endless_order, 0, 10),
+            Err(AnkiError::Interrupted)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn card_counts_by_queue() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col
+            .get_notetype_by_name("Basic (and reversed card)")?
+            .unwrap();
+        for _ in 0..3 {
+            NoteAdder::new(&nt).fields(&["front", "back"]).add(&mut col);
+        }
+        let mut cards = col.all_cards_for_search("")?;
+        cards.sort_unstable_by_key(|card| card.id);
+        let mut set_queue = |idx: usize, queue: CardQueue, ctype: CardType| -> Result<()> {
+            cards[idx].queue = queue;
+            cards[idx].ctype = ctype;
+            col.storage.update_card(&cards[idx])
+        };
+        set_queue(0, CardQueue::Learn, CardType::Learn)?;
+        set_queue(1, CardQueue::Review, CardType::Review)?;
+        set_queue(2, CardQueue::Suspended, CardType::Review)?;
+        set_queue(3, CardQueue::UserBuried, CardType::New)?;
+        set_queue(4, CardQueue::PreviewRepeat, CardType::Review)?;
+
+        assert_eq!(
+            col.search_card_count_by_queue("")?,
+            QueueCounts {
+                new: 1,
+                learning: 1,
+                review: 2,
+                suspended: 1,
+                buried: 1,
+            }
+        );
+        assert_eq!(
+            col.search_card_count_by_queue("card:1")?,
+            QueueCounts {
+                new: 0,
+                learning: 1,
+                review: 1,
+                suspended: 1,
+                buried: 0,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn card_counts_by_queue_in_filtered_deck() -> Result<()> {
+        let mut col = Collection::new();
+        NoteAdder::basic(&mut col).add(&mut col);
+        let mut card = Card {
+            deck_id: DeckId(1),
+            ctype: CardType::Learn,
+            queue: CardQueue::DayLearn,
+            due: 123,
+            ..Default::default()
+        };
+        col.add_card(&mut card)?;
+
+        // without rescheduling, all cards are moved into the review queue
+        let mut filtered_deck = Deck::new_filtered();
+        filtered_deck.filtered_mut()?.reschedule = false;
+        col.add_or_update_deck(&mut filtered_deck)?;
+        assert_eq!(col.rebuild_filtered_deck(filtered_deck.id)?.output, 2);
+        assert_eq!(
+            col.search_card_count_by_queue("")?,
+            QueueCounts {
+                new: 1,
+                learning: 1,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
 }
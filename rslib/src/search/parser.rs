@@ -78,10 +78,13 @@ pub enum SearchNode {
     },
     Duplicates {
         notetype_id: NotetypeId,
+        field_ord: usize,
         text: String,
     },
     State(StateKind),
     Flag(u8),
+    /// Cards with any of the flags 1-7 set.
+    AnyFlag,
     NoteIds(String),
     CardIds(String),
     Property {
@@ -94,16 +97,22 @@ pub enum SearchNode {
     WordBoundary(String),
     CustomData(String),
     Preset(String),
+    /// Matches cards whose (original) deck uses the given preset. Unlike
+    /// [SearchNode::Preset], it is not affected by presets sharing a name.
+    PresetId(DeckConfigId),
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum PropertyKind {
     Due(i32),
+    DueHours(i32),
     Interval(u32),
     Reps(u32),
     Lapses(u32),
     Ease(f32),
     Position(u32),
+    CardsOfNote(u32),
+    LearningStep(u32),
     Rated(i32, RatingKind),
     Stability(f32),
     Difficulty(f32),
@@ -382,9 +391,13 @@ fn parse_template(s: &str) -> ParseResult<SearchNode> {
     }))
 }
 
-/// flag:0-7
+/// flag:0-7, flag:any or flag:none
 fn parse_flag(s: &str) -> ParseResult<SearchNode> {
-    if let Ok(flag) = s.parse::<u8>() {
+    if s.eq_ignore_ascii_case("any") {
+        Ok(SearchNode::AnyFlag)
+    } else if s.eq_ignore_ascii_case("none") {
+        Ok(SearchNode::Flag(0))
+    } else if let Ok(flag) = s.parse::<u8>() {
         if flag > 7 {
             Err(parse_failure(s, FailKind::InvalidFlag))
         } else {
@@ -407,11 +420,14 @@ fn parse_resched(s: &str) -> ParseResult<SearchNode> {
 fn parse_prop(prop_clause: &str) -> ParseResult<SearchNode> {
     let (tail, prop) = alt::<_, _, ParseError, _>((
         tag("ivl"),
+        tag("due-h"),
         tag("due"),
         tag("reps"),
         tag("lapses"),
         tag("ease"),
         tag("pos"),
+        tag("cards"),
+        tag("step"),
         tag("rated"),
         tag("resched"),
         tag("s"),
@@ -449,6 +465,7 @@ fn parse_prop(prop_clause: &str) -> ParseResult<SearchNode> {
     let kind = match prop {
         "ease" => PropertyKind::Ease(parse_f32(num, prop_clause)?),
         "due" => PropertyKind::Due(parse_i32(num, prop_clause)?),
+        "due-h" => PropertyKind::DueHours(parse_i32(num, prop_clause)?),
         "rated" => parse_prop_rated(num, prop_clause)?,
         "resched" => PropertyKind::Rated(
             parse_negative_i32(num, prop_clause)?,
@@ -458,6 +475,8 @@ fn parse_prop(prop_clause: &str) -> ParseResult<SearchNode> {
         "reps" => PropertyKind::Reps(parse_u32(num, prop_clause)?),
         "lapses" => PropertyKind::Lapses(parse_u32(num, prop_clause)?),
         "pos" => PropertyKind::Position(parse_u32(num, prop_clause)?),
+        "cards" => PropertyKind::CardsOfNote(parse_u32(num, prop_clause)?),
+        "step" => PropertyKind::LearningStep(parse_u32(num, prop_clause)?),
         "s" => PropertyKind::Stability(parse_f32(num, prop_clause)?),
         "d" => PropertyKind::Difficulty(parse_f32(num, prop_clause)?),
         "r" => PropertyKind::Retrievability(parse_f32(num, prop_clause)?),
@@ -637,13 +656,16 @@ fn check_id_list<'a>(s: &'a str, context: &str) -> ParseResult<'a, &'a str> {
     }
 }
 
-/// eg dupe:1231,hello
+/// eg dupe:1231,hello for the first field, or dupe:1231,2,hello for the
+/// field at ordinal 2
 fn parse_dupe(s: &str) -> ParseResult<SearchNode> {
     let mut it = s.splitn(2, ',');
     let ntid = parse_i64(it.next().unwrap(), s)?;
     if let Some(text) = it.next() {
+        let (field_ord, text) = split_dupe_field_ord(text).unwrap_or((0, text));
         Ok(SearchNode::Duplicates {
             notetype_id: ntid.into(),
+            field_ord,
             text: unescape_quotes_and_backslashes(text),
         })
     } else {
@@ -657,6 +679,19 @@ fn parse_dupe(s: &str) -> ParseResult<SearchNode> {
     }
 }
 
+/// If `text` starts with a field ordinal followed by a comma, return the
+/// ordinal and the remaining text. Only canonical numbers that fit a field
+/// index are treated as ordinals, so that first-field text of older searches
+/// can be restored from them if the notetype has no such field.
+pub(super) fn split_dupe_field_ord(text: &str) -> Option<(usize, &str)> {
+    let (ord, text) = text.split_once(',')?;
+    if ord.bytes().all(|b| b.is_ascii_digit()) && (ord == "0" || !ord.starts_with('0')) {
+        Some((ord.parse::<u16>().ok()? as usize, text))
+    } else {
+        None
+    }
+}
+
 fn parse_single_field<'a>(key: &'a str, val: &'a str) -> ParseResult<'a, SearchNode> {
     Ok(if let Some(stripped) = val.strip_prefix("re:") {
         SearchNode::SingleField {
@@ -914,8 +949,42 @@ mod test {
             parse("nid:1237123712,2,3")?,
             vec![Search(NoteIds("1237123712,2,3".into()))]
         );
+        assert_eq!(
+            parse("dupe:123,a,b")?,
+            vec![Search(Duplicates {
+                notetype_id: 123.into(),
+                field_ord: 0,
+                text: "a,b".into()
+            })]
+        );
+        assert_eq!(
+            parse("dupe:123,1,a,b")?,
+            vec![Search(Duplicates {
+                notetype_id: 123.into(),
+                field_ord: 1,
+                text: "a,b".into()
+            })]
+        );
+        assert_eq!(
+            parse("dupe:123,70000,a")?,
+            vec![Search(Duplicates {
+                notetype_id: 123.into(),
+                field_ord: 0,
+                text: "70000,a".into()
+            })]
+        );
+        assert_eq!(
+            parse("dupe:123,01,a")?,
+            vec![Search(Duplicates {
+                notetype_id: 123.into(),
+                field_ord: 0,
+                text: "01,a".into()
+            })]
+        );
         assert_eq!(parse("is:due")?, vec![Search(State(StateKind::Due))]);
         assert_eq!(parse("flag:3")?, vec![Search(Flag(3))]);
+        assert_eq!(parse("flag:any")?, vec![Search(AnyFlag)]);
+        assert_eq!(parse("flag:none")?, vec![Search(Flag(0))]);
 
         assert_eq!(
             parse("prop:ivl>3")?,
@@ -105,9 +105,14 @@ impl crate::services::SearchService for Collection {
         let field_name = if input.field_name.is_empty() {
             None
         } else {
-            Some(input.field_name)
+            Some(input.field_name.as_str())
+        };
+        let repl = if input.regex {
+            input.replacement
+        } else {
+            // don't expand $1 etc. in plain text mode
+            input.replacement.replace('$', "$$")
         };
-        let repl = input.replacement;
 
         if nids.is_empty() {
             nids = self.search_notes_unordered("")?
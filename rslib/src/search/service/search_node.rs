@@ -35,6 +35,7 @@ impl TryFrom<anki_proto::search::SearchNode> for Node {
                 Filter::Nids(nids) => Node::Search(SearchNode::NoteIds(id_list_to_string(nids))),
                 Filter::Dupe(dupe) => Node::Search(SearchNode::Duplicates {
                     notetype_id: dupe.notetype_id.into(),
+                    field_ord: dupe.field_ord as usize,
                     text: dupe.first_field,
                 }),
                 Filter::FieldName(s) => Node::Search(SearchNode::SingleField {
@@ -2,6 +2,7 @@
 // License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::ops::Range;
 
@@ -139,9 +140,11 @@ impl SqlWriter<'_> {
             SearchNode::SingleField { field, text, is_re } => {
                 self.write_field(&norm(field), &self.norm_note(text), *is_re)?
             }
-            SearchNode::Duplicates { notetype_id, text } => {
-                self.write_dupe(*notetype_id, &self.norm_note(text))?
-            }
+            SearchNode::Duplicates {
+                notetype_id,
+                field_ord,
+                text,
+            } => self.write_dupe(*notetype_id, *field_ord, &self.norm_note(text))?,
             SearchNode::Regex(re) => self.write_regex(&self.norm_note(re), false)?,
             SearchNode::NoCombining(text) => self.write_unqualified(&self.norm_note(text), true)?,
             SearchNode::WordBoundary(text) => self.write_word_boundary(&self.norm_note(text))?,
@@ -172,6 +175,7 @@ impl SqlWriter<'_> {
             SearchNode::Flag(flag) => {
                 write!(self.sql, "(c.flags & 7) == {}", flag).unwrap();
             }
+            SearchNode::AnyFlag => write!(self.sql, "(c.flags & 7) != 0").unwrap(),
             SearchNode::NoteIds(nids) => {
                 write!(self.sql, "{} in ({})", self.note_id_column(), nids).unwrap();
             }
@@ -182,6 +186,7 @@ impl SqlWriter<'_> {
             SearchNode::CustomData(key) => self.write_custom_data(key)?,
             SearchNode::WholeCollection => write!(self.sql, "true").unwrap(),
             SearchNode::Preset(name) => self.write_deck_preset(name)?,
+            SearchNode::PresetId(dcid) => self.write_deck_preset_id(Some(*dcid))?,
         };
         Ok(())
     }
@@ -343,6 +348,19 @@ impl SqlWriter<'_> {
                     days = days
                 ).unwrap()
             }
+            PropertyKind::DueHours(hours) => {
+                // only (re)learning cards have a due timestamp; day-based due
+                // numbers can't be compared in hours. Cards in filtered decks
+                // keep their timestamp in due, and odue may be stale.
+                write!(
+                    self.sql,
+                    "(c.queue in ({lrn},{previewrepeat}) and ((c.due - {now}) / 3600) {op} {hours})",
+                    lrn = CardQueue::Learn as i8,
+                    previewrepeat = CardQueue::PreviewRepeat as i8,
+                    now = TimestampSecs::now(),
+                )
+                .unwrap()
+            }
             PropertyKind::Position(pos) => write!(
                 self.sql,
                 "(c.type = {t} and (case when c.odue != 0 then c.odue else c.due end) {op} {pos})",
@@ -351,6 +369,12 @@ impl SqlWriter<'_> {
                 pos = pos
             )
             .unwrap(),
+            PropertyKind::CardsOfNote(count) => write!(
+                self.sql,
+                "c.nid in (select nid from cards group by nid having count() {op} {count})"
+            )
+            .unwrap(),
+            PropertyKind::LearningStep(step) => self.write_learning_step(op, *step)?,
             PropertyKind::Interval(ivl) => write!(self.sql, "ivl {} {}", op, ivl).unwrap(),
             PropertyKind::Reps(reps) => write!(self.sql, "reps {} {}", op, reps).unwrap(),
             PropertyKind::Lapses(days) => write!(self.sql, "lapses {} {}", op, days).unwrap(),
@@ -395,6 +419,54 @@ impl SqlWriter<'_> {
         Ok(())
     }
 
+    /// (Re)learning cards whose current step, counted from 0, matches. The
+    /// lower part of `left` holds the number of remaining steps, so the
+    /// step depends on the number of steps in the preset of the card's home
+    /// deck.
+    fn write_learning_step(&mut self, op: &str, step: u32) -> Result<()> {
+        let configs = self.col.storage.get_deck_config_map()?;
+        let default_config = DeckConfig::default();
+        let mut learn_decks: HashMap<usize, Vec<DeckId>> = HashMap::new();
+        let mut relearn_decks: HashMap<usize, Vec<DeckId>> = HashMap::new();
+        for deck in self.col.storage.get_all_decks()? {
+            if let Some(dcid) = deck.config_id() {
+                let config = configs.get(&dcid).unwrap_or(&default_config);
+                learn_decks
+                    .entry(config.inner.learn_steps.len())
+                    .or_default()
+                    .push(deck.id);
+                relearn_decks
+                    .entry(config.inner.relearn_steps.len())
+                    .or_default()
+                    .push(deck.id);
+            }
+        }
+        let step_sql = |decks_by_step_count: HashMap<usize, Vec<DeckId>>| {
+            let mut sql = "max(0, (case".to_string();
+            for (count, dids) in decks_by_step_count
+                .into_iter()
+                .sorted_unstable_by_key(|(count, _)| *count)
+            {
+                sql.push_str(" when (case when c.odid != 0 then c.odid else c.did end) in ");
+                ids_to_string(&mut sql, dids);
+                write!(sql, " then {count}").unwrap();
+            }
+            sql.push_str(" else 0 end) - c.left % 1000)");
+            sql
+        };
+        write!(
+            self.sql,
+            "((c.type = {learn} and {learn_step} {op} {step}) or \
+            (c.type = {relearn} and {relearn_step} {op} {step}))",
+            learn = CardType::Learn as i8,
+            relearn = CardType::Relearn as i8,
+            learn_step = step_sql(learn_decks),
+            relearn_step = step_sql(relearn_decks),
+        )
+        .unwrap();
+        Ok(())
+    }
+
     fn write_custom_data(&mut self, key: &str) -> Result<()> {
         write!(self.sql, "extract_custom_data(c.data, '{key}') is not null").unwrap();
 
@@ -736,14 +808,28 @@ impl SqlWriter<'_> {
         }
     }
 
-    fn write_dupe(&mut self, ntid: NotetypeId, text: &str) -> Result<()> {
+    /// Only the first field has a stored checksum, so other fields are
+    /// compared against every note of the notetype. If the notetype has no
+    /// field at the ordinal, the search predates ordinals, and the ordinal
+    /// is part of the first field's text.
+    fn write_dupe(&mut self, ntid: NotetypeId, field_ord: usize, text: &str) -> Result<()> {
+        let field_count = self.col.get_notetype(ntid)?.map_or(0, |nt| nt.fields.len());
+        let legacy_text;
+        let (field_ord, text) = if field_ord > 0 && field_ord >= field_count {
+            legacy_text = format!("{field_ord},{text}");
+            (0, legacy_text.as_str())
+        } else {
+            (field_ord, text)
+        };
         let text_nohtml = strip_html_preserving_media_filenames(text);
-        let csum = field_checksum(text_nohtml.as_ref());
+        let candidates = if field_ord == 0 {
+            let csum = field_checksum(text_nohtml.as_ref());
+            self.col.storage.note_fields_by_checksum(ntid, csum)?
+        } else {
+            self.col.storage.note_fields_at_index(ntid, field_ord)?
+        };
 
-        let nids: Vec<_> = self
-            .col
-            .storage
-            .note_fields_by_checksum(ntid, csum)?
+        let nids: Vec<_> = candidates
             .into_iter()
             .filter_map(|(nid, field)| {
                 if strip_html_preserving_media_filenames(&field) == text_nohtml {
@@ -839,6 +925,10 @@ impl SqlWriter<'_> {
     }
     fn write_deck_preset(&mut self, name: &str) -> Result<()> {
         let dcid = self.col.storage.get_deck_config_id_by_name(name)?;
+        self.write_deck_preset_id(dcid)
+    }
+
+    fn write_deck_preset_id(&mut self, dcid: Option<DeckConfigId>) -> Result<()> {
         let mut str_ids = String::new();
         let deck_ids = self
             .col
@@ -971,10 +1061,12 @@ impl SearchNode {
             SearchNode::Rated { .. } => RequiredTable::Cards,
             SearchNode::State(_) => RequiredTable::Cards,
             SearchNode::Flag(_) => RequiredTable::Cards,
+            SearchNode::AnyFlag => RequiredTable::Cards,
             SearchNode::CardIds(_) => RequiredTable::Cards,
             SearchNode::Property { .. } => RequiredTable::Cards,
             SearchNode::CustomData { .. } => RequiredTable::Cards,
             SearchNode::Preset(_) => RequiredTable::Cards,
+            SearchNode::PresetId(_) => RequiredTable::Cards,
 
             SearchNode::UnqualifiedText(_) => RequiredTable::Notes,
             SearchNode::SingleField { .. } => RequiredTable::Notes,
@@ -1138,6 +1230,8 @@ mod test {
         // flags
         assert_eq!(s(ctx, "flag:2"), ("((c.flags & 7) == 2)".into(), vec![]));
         assert_eq!(s(ctx, "flag:0"), ("((c.flags & 7) == 0)".into(), vec![]));
+        assert_eq!(s(ctx, "flag:none"), ("((c.flags & 7) == 0)".into(), vec![]));
+        assert_eq!(s(ctx, "flag:any"), ("((c.flags & 7) != 0)".into(), vec![]));
 
         // dupes
         assert_eq!(s(ctx, "dupe:123,test"), ("(n.id in ())".into(), vec![]));
@@ -1214,6 +1308,10 @@ mod test {
         // props
         assert_eq!(s(ctx, "prop:lapses=3").0, "(lapses = 3)".to_string());
         assert_eq!(s(ctx, "prop:ease>=2.5").0, "(factor >= 2500)".to_string());
+        assert_eq!(
+            s(ctx, "prop:cards>1").0,
+            "(c.nid in (select nid from cards group by nid having count() > 1))"
+        );
         assert_eq!(
             s(ctx, "prop:due!=-1").0,
             format!(
@@ -9,6 +9,7 @@ use regex::Regex;
 use crate::notetype::NotetypeId as NotetypeIdType;
 use crate::prelude::*;
 use crate::search::parser::parse;
+use crate::search::parser::split_dupe_field_ord;
 use crate::search::parser::Node;
 use crate::search::parser::PropertyKind;
 use crate::search::parser::RatingKind;
@@ -81,9 +82,14 @@ fn write_search_node(node: &SearchNode) -> String {
         Notetype(s) => maybe_quote(&format!("note:{}", s)),
         Rated { days, ease } => write_rated(days, ease),
         Tag { tag, is_re } => write_single_field("tag", tag, *is_re),
-        Duplicates { notetype_id, text } => write_dupe(notetype_id, text),
+        Duplicates {
+            notetype_id,
+            field_ord,
+            text,
+        } => write_dupe(notetype_id, *field_ord, text),
         State(k) => write_state(k),
         Flag(u) => format!("flag:{}", u),
+        AnyFlag => "flag:any".to_string(),
         NoteIds(s) => format!("nid:{}", s),
         CardIds(s) => format!("cid:{}", s),
         Property { operator, kind } => write_property(operator, kind),
@@ -93,6 +99,8 @@ fn write_search_node(node: &SearchNode) -> String {
         WordBoundary(s) => maybe_quote(&format!("w:{}", s)),
         CustomData(k) => maybe_quote(&format!("has-cd:{}", k)),
         Preset(s) => maybe_quote(&format!("preset:{}", s)),
+        // not exposed on the GUI end
+        PresetId(_) => "".to_string(),
     }
 }
 
@@ -142,9 +150,15 @@ fn write_rated(days: &u32, ease: &RatingKind) -> String {
 }
 
 /// Escape double quotes and backslashes: \"
-fn write_dupe(notetype_id: &NotetypeId, text: &str) -> String {
+/// The field ordinal is only included if it is not the first field, or the
+/// text could otherwise be mistaken for one.
+fn write_dupe(notetype_id: &NotetypeId, field_ord: usize, text: &str) -> String {
     let esc = text.replace('\\', r"\\");
-    maybe_quote(&format!("dupe:{},{}", notetype_id, esc))
+    if field_ord == 0 && split_dupe_field_ord(text).is_none() {
+        maybe_quote(&format!("dupe:{},{}", notetype_id, esc))
+    } else {
+        maybe_quote(&format!("dupe:{},{},{}", notetype_id, field_ord, esc))
+    }
 }
 
 fn write_state(kind: &StateKind) -> String {
@@ -168,11 +182,14 @@ fn write_property(operator: &str, kind: &PropertyKind) -> String {
     use PropertyKind::*;
     match kind {
         Due(i) => format!("prop:due{}{}", operator, i),
+        DueHours(i) => format!("prop:due-h{}{}", operator, i),
         Interval(u) => format!("prop:ivl{}{}", operator, u),
         Reps(u) => format!("prop:reps{}{}", operator, u),
         Lapses(u) => format!("prop:lapses{}{}", operator, u),
         Ease(f) => format!("prop:ease{}{}", operator, f),
         Position(u) => format!("prop:pos{}{}", operator, u),
+        CardsOfNote(u) => format!("prop:cards{}{}", operator, u),
+        LearningStep(u) => format!("prop:step{}{}", operator, u),
         Stability(u) => format!("prop:s{}{}", operator, u),
         Difficulty(u) => format!("prop:d{}{}", operator, u),
         Retrievability(u) => format!("prop:r{}{}", operator, u),
@@ -227,6 +244,17 @@ mod test {
         assert_eq!(r#""aNd" "oR""#, normalize_search(r#""aNd" "oR""#).unwrap());
         // normalize numbers
         assert_eq!("prop:ease>1", normalize_search("prop:ease>1.0").unwrap());
+        // field ordinals of dupe searches are only written when needed
+        assert_eq!("dupe:1,a,b", normalize_search("dupe:1,0,a,b").unwrap());
+        assert_eq!("dupe:1,2,a", normalize_search("dupe:1,2,a").unwrap());
+        assert_eq!(
+            "dupe:1,0,2,a",
+            write_nodes(&[Node::Search(SearchNode::Duplicates {
+                notetype_id: 1.into(),
+                field_ord: 0,
+                text: "2,a".into(),
+            })])
+        );
     }
 
     #[test]
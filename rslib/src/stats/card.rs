@@ -5,10 +5,38 @@ use fsrs::FSRS;
 
 use crate::card::CardQueue;
 use crate::card::CardType;
+use crate::card::FsrsMemoryState;
 use crate::prelude::*;
 use crate::revlog::RevlogEntry;
 use crate::scheduler::timing::is_unix_epoch_timestamp;
 
+/// A card's scheduling state, with its due value interpreted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardSchedulingInfo {
+    pub queue: CardQueue,
+    pub ctype: CardType,
+    pub due: CardDue,
+    /// In days.
+    pub interval: u32,
+    /// 10x the percentage, eg 2500 for 250%.
+    pub ease_factor: u16,
+    pub reps: u32,
+    pub lapses: u32,
+    /// Only provided when FSRS is enabled.
+    pub memory_state: Option<FsrsMemoryState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardDue {
+    /// Position in the new queue.
+    Position(i32),
+    /// The time a learning card is due, or the day a review card is due,
+    /// with its date as YYYY-mm-dd.
+    Date { stamp: TimestampSecs, date: String },
+    /// A new card in a filtered deck whose original position is unknown.
+    Unknown,
+}
+
 impl Collection {
     pub fn card_stats(&mut self, cid: CardId) -> Result<anki_proto::stats::CardStatsResponse> {
         let card = self.storage.get_card(cid)?.or_not_found(cid)?;
@@ -83,6 +111,33 @@ impl Collection {
         })
     }
 
+    pub fn card_scheduling_info(&mut self, cid: CardId) -> Result<CardSchedulingInfo> {
+        let card = self.storage.get_card(cid)?.or_not_found(cid)?;
+        let due = match self.due_date_and_position(&card)? {
+            (Some(stamp), _) => {
+                let stamp = TimestampSecs(stamp);
+                CardDue::Date {
+                    stamp,
+                    date: stamp.date_string(),
+                }
+            }
+            (None, Some(position)) => CardDue::Position(position),
+            (None, None) => CardDue::Unknown,
+        };
+        Ok(CardSchedulingInfo {
+            queue: card.queue,
+            ctype: card.ctype,
+            due,
+            interval: card.interval,
+            ease_factor: card.ease_factor,
+            reps: card.reps,
+            lapses: card.lapses,
+            memory_state: card
+                .memory_state
+                .filter(|_| self.get_config_bool(BoolKey::Fsrs)),
+        })
+    }
+
     fn due_date_and_position(&mut self, card: &Card) -> Result<(Option<i64>, Option<i32>)> {
         let due = if card.original_due != 0 {
             card.original_due
@@ -145,6 +200,7 @@ fn stats_revlog_entry(
 mod test {
     use super::*;
     use crate::search::SortMode;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn stats() -> Result<()> {
@@ -160,4 +216,44 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn scheduling_info() -> Result<()> {
+        let mut col = Collection::new();
+        let note = NoteAdder::basic(&mut col).add(&mut col);
+        let mut card = col.storage.all_cards_of_note(note.id)?.pop().unwrap();
+        let info = col.card_scheduling_info(card.id)?;
+        assert_eq!(info.queue, CardQueue::New);
+        assert_eq!(info.due, CardDue::Position(1));
+
+        let today = col.timing_today()?.days_elapsed as i32;
+        card.ctype = CardType::Review;
+        card.queue = CardQueue::Review;
+        card.due = today + 2;
+        card.interval = 5;
+        card.memory_state = Some(FsrsMemoryState {
+            stability: 5.0,
+            difficulty: 5.0,
+        });
+        col.storage.update_card(&card)?;
+        let info = col.card_scheduling_info(card.id)?;
+        let CardDue::Date { stamp, date } = info.due else {
+            panic!("{:?}", info.due);
+        };
+        assert_eq!(
+            stamp.elapsed_days_since(TimestampSecs::now().adding_secs(-60)),
+            2
+        );
+        assert_eq!(date, stamp.date_string());
+        assert_eq!(info.interval, 5);
+        // memory state is only exposed with FSRS on
+        assert_eq!(info.memory_state, None);
+        col.set_config_bool(BoolKey::Fsrs, true, false)?;
+        assert_eq!(
+            col.card_scheduling_info(card.id)?.memory_state,
+            card.memory_state
+        );
+
+        Ok(())
+    }
 }
@@ -3,7 +3,13 @@
 
 mod card;
 mod graphs;
+mod retention;
 mod service;
 mod today;
 
+pub use card::CardDue;
+pub use card::CardSchedulingInfo;
+pub use retention::IntervalBucket;
+pub use retention::RetentionStats;
 pub use today::studied_today;
+pub use today::ReviewCounts;
@@ -0,0 +1,233 @@
+// Copyright: Ankitects Pty Ltd and contributors
+// License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
+
+use crate::prelude::*;
+use crate::revlog::RevlogReviewKind;
+use crate::search::SortMode;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionStats {
+    /// Reviews answered Hard, Good or Easy.
+    pub passed: u32,
+    /// Reviews answered Again.
+    pub failed: u32,
+}
+
+impl RetentionStats {
+    pub fn total(&self) -> u32 {
+        self.passed + self.failed
+    }
+
+    /// Percentage of passed reviews, or None if there were no reviews.
+    pub fn retention(&self) -> Option<f32> {
+        (self.total() > 0).then(|| self.passed as f32 / self.total() as f32 * 100.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalBucket {
+    OneDay,
+    UpToAWeek,
+    UpToAMonth,
+    UpToThreeMonths,
+    Longer,
+}
+
+impl IntervalBucket {
+    const ALL: [IntervalBucket; 5] = [
+        Self::OneDay,
+        Self::UpToAWeek,
+        Self::UpToAMonth,
+        Self::UpToThreeMonths,
+        Self::Longer,
+    ];
+
+    fn from_days(interval: u32) -> Self {
+        match interval {
+            0..=1 => Self::OneDay,
+            2..=7 => Self::UpToAWeek,
+            8..=30 => Self::UpToAMonth,
+            31..=90 => Self::UpToThreeMonths,
+            _ => Self::Longer,
+        }
+    }
+}
+
+impl Collection {
+    /// Retention of review cards in the given deck, between `from_day` and
+    /// `to_day` inclusive. Days are counted from the collection's creation,
+    /// like card due dates. Learning, relearning and filtered reviews are not
+    /// included.
+    /// As the review log does not record a deck, reviews are attributed to
+    /// their card's current home deck.
+    pub fn deck_retention(
+        &mut self,
+        did: DeckId,
+        include_subdecks: bool,
+        from_day: u32,
+        to_day: u32,
+    ) -> Result<RetentionStats> {
+        let dids = self.deck_ids_maybe_with_children(did, include_subdecks)?;
+        let (start, end) = self.day_range(from_day, to_day)?;
+        let (passed, failed) = self
+            .storage
+            .review_pass_and_fail_counts(&dids, start, end)?;
+
+        Ok(RetentionStats { passed, failed })
+    }
+
+    /// For the cards matching the search, the number of passed and total
+    /// reviews between `from_day` and `to_day` inclusive, grouped by the
+    /// interval the cards had when they were reviewed. All buckets are
+    /// returned, in ascending order.
+    pub fn true_retention_by_interval(
+        &mut self,
+        search: &str,
+        from_day: u32,
+        to_day: u32,
+    ) -> Result<Vec<(IntervalBucket, u32, u32)>> {
+        let (start, end) = self.day_range(from_day, to_day)?;
+        let guard = self.search_cards_into_table(search, SortMode::NoOrder)?;
+        let revlog = guard
+            .col
+            .storage
+            .get_revlog_entries_for_searched_cards_after_stamp(start)?;
+        let mut buckets: Vec<_> = IntervalBucket::ALL
+            .iter()
+            .map(|bucket| (*bucket, 0, 0))
+            .collect();
+        for entry in revlog {
+            if entry.review_kind != RevlogReviewKind::Review
+                || entry.button_chosen == 0
+                || entry.last_interval < 1
+                || entry.id.as_secs() >= end
+            {
+                continue;
+            }
+            let bucket =
+                &mut buckets[IntervalBucket::from_days(entry.last_interval as u32) as usize];
+            if entry.button_chosen > 1 {
+                bucket.1 += 1;
+            }
+            bucket.2 += 1;
+        }
+
+        Ok(buckets)
+    }
+
+    /// Start of `from_day`, and end of `to_day`.
+    fn day_range(&mut self, from_day: u32, to_day: u32) -> Result<(TimestampSecs, TimestampSecs)> {
+        let timing = self.timing_today()?;
+        let day_start = |day: u32| {
+            timing
+                .next_day_at
+                .adding_secs((day as i64 - timing.days_elapsed as i64 - 1) * 86_400)
+        };
+        Ok((day_start(from_day), day_start(to_day + 1)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::revlog::RevlogEntry;
+    use crate::tests::DeckAdder;
+    use crate::tests::NoteAdder;
+
+    #[test]
+    fn deck_retention() -> Result<()> {
+        let mut col = Collection::new();
+        let parent = DeckAdder::new("parent").add(&mut col);
+        let child = DeckAdder::new("parent::child").add(&mut col);
+        let now = TimestampMillis::now();
+        let today = col.timing_today()?.days_elapsed;
+        for (deck, button, kind, days_ago) in [
+            (parent.id, 1, RevlogReviewKind::Review, 0),
+            (parent.id, 3, RevlogReviewKind::Review, 0),
+            (child.id, 2, RevlogReviewKind::Review, 0),
+            (child.id, 4, RevlogReviewKind::Review, 0),
+            // learning steps and older reviews are ignored
+            (parent.id, 1, RevlogReviewKind::Learning, 0),
+            (parent.id, 1, RevlogReviewKind::Review, 10),
+        ] {
+            col.add_revlog_entry_for_new_note(
+                deck,
+                RevlogEntry {
+                    id: RevlogId(now.0 - days_ago * 86_400_000),
+                    button_chosen: button,
+                    review_kind: kind,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let stats = col.deck_retention(parent.id, false, today, today)?;
+        assert_eq!(
+            stats,
+            RetentionStats {
+                passed: 1,
+                failed: 1
+            }
+        );
+        assert_eq!(stats.retention(), Some(50.0));
+        let stats = col.deck_retention(parent.id, true, today, today)?;
+        assert_eq!(
+            stats,
+            RetentionStats {
+                passed: 3,
+                failed: 1
+            }
+        );
+        assert_eq!(
+            col.deck_retention(child.id, false, today, today)?.total(),
+            2
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn true_retention_by_interval() -> Result<()> {
+        let mut col = Collection::new();
+        let now = TimestampMillis::now();
+        let today = col.timing_today()?.days_elapsed;
+        let note = NoteAdder::basic(&mut col).add(&mut col);
+        let cid = col.storage.card_ids_of_notes(&[note.id])?[0];
+        for (idx, (button, last_interval)) in [(3, 1), (1, 1), (3, 5), (4, 100), (1, 100), (2, 90)]
+            .into_iter()
+            .enumerate()
+        {
+            let entry = RevlogEntry {
+                id: RevlogId(now.0 + idx as i64),
+                cid,
+                button_chosen: button,
+                last_interval,
+                review_kind: RevlogReviewKind::Review,
+                ..Default::default()
+            };
+            col.storage.add_revlog_entry(&entry, true)?;
+        }
+
+        assert_eq!(
+            col.true_retention_by_interval("", today, today)?,
+            [
+                (IntervalBucket::OneDay, 1, 2),
+                (IntervalBucket::UpToAWeek, 1, 1),
+                (IntervalBucket::UpToAMonth, 0, 0),
+                (IntervalBucket::UpToThreeMonths, 1, 1),
+                (IntervalBucket::Longer, 1, 2),
+            ]
+        );
+        // other days and cards are excluded
+        assert_eq!(
+            col.true_retention_by_interval("", today + 1, today + 1)?[0],
+            (IntervalBucket::OneDay, 0, 0)
+        );
+        assert_eq!(
+            col.true_retention_by_interval("cid:1", today, today)?[0],
+            (IntervalBucket::OneDay, 0, 0)
+        );
+
+        Ok(())
+    }
+}
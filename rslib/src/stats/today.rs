@@ -19,19 +19,45 @@ pub fn studied_today(cards: u32, secs: f32, tr: &I18n) -> String {
         .into()
 }
 
+/// Answers logged in a deck, by kind.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReviewCounts {
+    /// First answers of new cards.
+    pub new: u32,
+    /// Further answers of cards in (re)learning.
+    pub learning: u32,
+    /// Answers of review cards, including those in filtered decks.
+    pub review: u32,
+}
+
 impl Collection {
     pub fn studied_today(&mut self) -> Result<String> {
         let timing = self.timing_today()?;
         let today = self.storage.studied_today(timing.next_day_at)?;
         Ok(studied_today(today.cards, today.seconds as f32, &self.tr))
     }
+
+    /// The answers logged since today's rollover for cards in the given deck.
+    /// Answers are attributed to decks like in [Collection::deck_retention].
+    pub fn reviews_done_today(
+        &mut self,
+        did: DeckId,
+        include_subdecks: bool,
+    ) -> Result<ReviewCounts> {
+        let dids = self.deck_ids_maybe_with_children(did, include_subdecks)?;
+        let start = self.timing_today()?.next_day_at.adding_secs(-86_400);
+        self.storage.review_counts_since(&dids, start)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use anki_i18n::I18n;
 
-    use super::studied_today;
+    use super::*;
+    use crate::revlog::RevlogEntry;
+    use crate::revlog::RevlogReviewKind;
+    use crate::tests::DeckAdder;
 
     #[test]
     fn today() {
@@ -42,4 +68,52 @@ mod test {
             "Studied 3 cards in 13 seconds today (4.33s/card)"
         );
     }
+
+    #[test]
+    fn reviews_done_today() -> Result<()> {
+        let mut col = Collection::new();
+        let parent = DeckAdder::new("parent").add(&mut col);
+        let child = DeckAdder::new("parent::child").add(&mut col);
+        let now = TimestampMillis::now();
+        for (deck, kind, last_interval, days_ago) in [
+            (parent.id, RevlogReviewKind::Learning, 0, 0),
+            (parent.id, RevlogReviewKind::Learning, -600, 0),
+            (parent.id, RevlogReviewKind::Relearning, -600, 0),
+            (parent.id, RevlogReviewKind::Review, 10, 0),
+            (child.id, RevlogReviewKind::Review, 10, 0),
+            // manual changes and older answers are ignored
+            (parent.id, RevlogReviewKind::Manual, 0, 0),
+            (parent.id, RevlogReviewKind::Review, 10, 2),
+        ] {
+            col.add_revlog_entry_for_new_note(
+                deck,
+                RevlogEntry {
+                    id: RevlogId(now.0 - days_ago * 86_400_000),
+                    button_chosen: 3,
+                    review_kind: kind,
+                    last_interval,
+                    ..Default::default()
+                },
+            );
+        }
+
+        assert_eq!(
+            col.reviews_done_today(parent.id, false)?,
+            ReviewCounts {
+                new: 1,
+                learning: 2,
+                review: 1
+            }
+        );
+        assert_eq!(col.reviews_done_today(parent.id, true)?.review, 2);
+        assert_eq!(
+            col.reviews_done_today(child.id, false)?,
+            ReviewCounts {
+                review: 1,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
 }
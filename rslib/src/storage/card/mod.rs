@@ -4,6 +4,7 @@
 pub(crate) mod data;
 pub(crate) mod filtered;
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::fmt;
@@ -96,6 +97,7 @@ fn row_to_new_card(row: &Row) -> result::Result<NewCard, rusqlite::Error> {
         current_deck_id: row.get(4)?,
         original_deck_id: row.get(5)?,
         hash: 0,
+        field_value: None,
     })
 }
 
@@ -381,6 +383,15 @@ impl super::SqliteStorage {
             .map_err(Into::into)
     }
 
+    /// Review cards with a due number too large to be a day number. Uses the
+    /// same threshold as fix_due_other.sql.
+    pub(crate) fn review_card_ids_with_invalid_due(&self) -> Result<Vec<CardId>> {
+        self.db
+            .prepare("select id from cards where queue = ? and due > 100000 order by id")?
+            .query_and_then([CardQueue::Review as i8], |r| Ok(CardId(r.get(0)?)))?
+            .collect()
+    }
+
     pub(crate) fn all_filtered_cards_by_deck(&self) -> Result<Vec<(CardId, DeckId)>> {
         self.db
             .prepare("select id, did from cards where odid > 0")?
@@ -395,6 +406,54 @@ impl super::SqliteStorage {
             .map_err(Into::into)
     }
 
+    /// Number of review and interday learning cards due on each day between
+    /// `min_ivl` and `max_ivl` days from `today`, keyed by days from `today`.
+    /// Days without due cards are omitted.
+    pub(crate) fn review_card_counts_by_day(
+        &self,
+        today: u32,
+        min_ivl: u32,
+        max_ivl: u32,
+    ) -> Result<HashMap<u32, usize>> {
+        self.db
+            .prepare_cached(
+                "select (case when odid > 0 then odue else due end) as day, count() \
+                 from cards where queue in (?, ?) \
+                 and (case when odid > 0 then odue else due end) between ? and ? group by day",
+            )?
+            .query_and_then(
+                params![
+                    CardQueue::Review as i8,
+                    CardQueue::DayLearn as i8,
+                    today + min_ivl,
+                    today + max_ivl
+                ],
+                |r| -> Result<_> { Ok((r.get::<_, u32>(0)? - today, r.get(1)?)) },
+            )?
+            .collect()
+    }
+
+    /// Number of review cards in the review queue of the given decks that are
+    /// due on the given day. New cards in non-rescheduling filtered decks are
+    /// also in the review queue, but their `odue` is a position.
+    pub(crate) fn review_cards_due_on_day_in_decks(
+        &self,
+        dids: &[DeckId],
+        day: u32,
+    ) -> Result<usize> {
+        let mut sql = format!(
+            "select count() from cards where queue = {} and type = {} \
+             and (case when odid > 0 then odue else due end) = ? and did in ",
+            CardQueue::Review as i8,
+            CardType::Review as i8,
+        );
+        ids_to_string(&mut sql, dids);
+        self.db
+            .prepare(&sql)?
+            .query_row([day], |r| r.get(0))
+            .map_err(Into::into)
+    }
+
     pub(crate) fn get_card_by_ordinal(&self, nid: NoteId, ord: u16) -> Result<Option<Card>> {
         self.db
             .prepare_cached(concat!(
@@ -429,6 +488,14 @@ impl super::SqliteStorage {
             .collect()
     }
 
+    /// Cards whose current deck is `did`, not including child decks.
+    pub(crate) fn all_cards_in_single_deck_full(&self, did: DeckId) -> Result<Vec<Card>> {
+        self.db
+            .prepare_cached(concat!(include_str!("get_card.sql"), " where did = ?"))?
+            .query_and_then([did], |r| row_to_card(r).map_err(Into::into))?
+            .collect()
+    }
+
     pub(crate) fn all_cards_of_notes_above_ordinal(
         &mut self,
         note_ids: &[NoteId],
@@ -563,6 +630,23 @@ impl super::SqliteStorage {
             .collect()
     }
 
+    /// The number of searched cards in each (queue, type) combination.
+    /// The number of searched cards with each queue and type, and whether
+    /// they are in a filtered deck.
+    pub(crate) fn searched_card_queue_and_type_counts(
+        &self,
+    ) -> Result<Vec<(CardQueue, CardType, bool, usize)>> {
+        self.db
+            .prepare_cached(concat!(
+                "select queue, type, odid != 0, count() from cards ",
+                "where id in (select cid from search_cids) group by queue, type, odid != 0"
+            ))?
+            .query_and_then([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect()
+    }
+
     /// Cards will arrive in card id order, not search order.
     pub(crate) fn for_each_card_in_search<F>(&self, mut func: F) -> Result<()>
     where
@@ -325,6 +325,20 @@ impl SqliteStorage {
             .collect()
     }
 
+    /// Home decks of the siblings of the cards in `did`, with the most
+    /// common deck first.
+    pub(crate) fn sibling_deck_ids(&self, did: DeckId) -> Result<Vec<DeckId>> {
+        self.db
+            .prepare(
+                "select (case when s.odid = 0 then s.did else s.odid end) as sdid
+from cards c join cards s on s.nid = c.nid
+where c.did = ?1 and s.did != ?1
+group by sdid order by count() desc",
+            )?
+            .query_and_then([did], |r| r.get(0).map_err(Into::into))?
+            .collect()
+    }
+
     pub(crate) fn deck_is_empty(&self, did: DeckId) -> Result<bool> {
         self.db
             .prepare_cached("select null from cards where did=?")?
@@ -137,6 +137,17 @@ impl super::SqliteStorage {
             .map_err(Into::into)
     }
 
+    /// Ids of notes that have no cards, in ascending order.
+    pub(crate) fn note_ids_without_cards(&self) -> Result<Vec<NoteId>> {
+        self.db
+            .prepare_cached(
+                "select id from notes n where not exists
+  (select 1 from cards c where c.nid = n.id) order by id",
+            )?
+            .query_and_then([], |row| Ok(row.get(0)?))?
+            .collect()
+    }
+
     pub(crate) fn clear_pending_note_usns(&self) -> Result<()> {
         self.db
             .prepare("update notes set usn = 0 where usn = -1")?
@@ -178,6 +189,39 @@ impl super::SqliteStorage {
             .collect()
     }
 
+    /// Returns [(nid, field)] of all notes of a notetype.
+    pub(crate) fn note_fields_at_index(
+        &self,
+        ntid: NotetypeId,
+        field_ord: usize,
+    ) -> Result<Vec<(NoteId, String)>> {
+        self.db
+            .prepare_cached("select id, field_at_index(flds, ?) from notes where mid = ?")?
+            .query_and_then(params![field_ord, ntid], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect()
+    }
+
+    /// Returns [(nid, field)] of the notes of a notetype, in ascending note id
+    /// order. For the first field, only notes that share their checksum with
+    /// another note are included.
+    pub(crate) fn note_fields_with_possible_duplicates(
+        &self,
+        ntid: NotetypeId,
+        field_ord: usize,
+    ) -> Result<Vec<(NoteId, String)>> {
+        let sql = if field_ord == 0 {
+            "select id, field_at_index(flds, ?2) from notes where mid = ?1 and csum in \
+             (select csum from notes where mid = ?1 group by csum having count() > 1) \
+             order by id"
+        } else {
+            "select id, field_at_index(flds, ?2) from notes where mid = ?1 order by id"
+        };
+        self.db
+            .prepare(sql)?
+            .query_and_then(params![ntid, field_ord], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect()
+    }
+
     /// Returns [(nid, field 0)] of notes with the same checksum.
     /// The caller should strip the fields and compare to see if they actually
     /// match.
@@ -239,6 +283,45 @@ impl super::SqliteStorage {
         Ok(seen)
     }
 
+    /// Note counts for all tags referenced by notes and their parents, keyed
+    /// by name with `\x1f` separating the components. Each entry holds the
+    /// number of notes with exactly that tag, and the number with that tag or
+    /// any of its children. The number of notes with any tag is returned as
+    /// well.
+    pub(crate) fn note_counts_by_tag(&self) -> Result<(u32, HashMap<UniCase<String>, (u32, u32)>)> {
+        let mut stmt = self
+            .db
+            .prepare_cached("select tags from notes where tags != ''")?;
+        let mut query = stmt.query([])?;
+        let mut tagged_notes = 0;
+        let mut counts: HashMap<UniCase<String>, (u32, u32)> = HashMap::new();
+        while let Some(rows) = query.next()? {
+            let tags = rows.get_ref_unwrap(0).as_str()?.replace("::", "\x1f");
+            let mut direct = HashSet::new();
+            let mut with_children = HashSet::new();
+            for tag in split_tags(&tags) {
+                let mut tag_unicase = UniCase::new(tag);
+                direct.insert(tag_unicase);
+                with_children.insert(tag_unicase);
+                while let Some(parent_name) = immediate_parent_name_unicase(tag_unicase) {
+                    with_children.insert(parent_name);
+                    tag_unicase = parent_name;
+                }
+            }
+            if direct.is_empty() {
+                continue;
+            }
+            tagged_notes += 1;
+            for tag in direct {
+                counts.entry(UniCase::new(tag.to_string())).or_default().0 += 1;
+            }
+            for tag in with_children {
+                counts.entry(UniCase::new(tag.to_string())).or_default().1 += 1;
+            }
+        }
+        Ok((tagged_notes, counts))
+    }
+
     pub(crate) fn get_note_tags_by_id(&mut self, note_id: NoteId) -> Result<Option<NoteTags>> {
         self.db
             .prepare_cached(&format!("{} where id = ?", include_str!("get_tags.sql")))?
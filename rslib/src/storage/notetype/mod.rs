@@ -20,6 +20,7 @@ use crate::notetype::NoteField;
 use crate::notetype::NoteFieldConfig;
 use crate::notetype::NotetypeConfig;
 use crate::notetype::NotetypeSchema11;
+use crate::notetype::NotetypeSummary;
 use crate::prelude::*;
 
 fn row_to_notetype_core(row: &Row) -> Result<Notetype> {
@@ -406,4 +407,29 @@ impl SqliteStorage {
             .query_and_then([notetype_id], |row| Ok(row.get(0)?))?
             .collect()
     }
+
+    pub(crate) fn get_template_names(&self, notetype_id: NotetypeId) -> Result<Vec<String>> {
+        self.db
+            .prepare_cached("SELECT name FROM templates WHERE ntid = ? ORDER BY ord")?
+            .query_and_then([notetype_id], |row| Ok(row.get(0)?))?
+            .collect()
+    }
+
+    /// All notetypes, sorted by name.
+    pub(crate) fn get_notetype_summaries(&self) -> Result<Vec<NotetypeSummary>> {
+        self.db
+            .prepare_cached(concat!(include_str!("get_notetype.sql"), " ORDER BY name"))?
+            .query_and_then([], row_to_notetype_core)?
+            .map(|nt| {
+                let nt = nt?;
+                Ok(NotetypeSummary {
+                    kind: nt.config.kind(),
+                    field_names: self.get_field_names(nt.id)?,
+                    template_names: self.get_template_names(nt.id)?,
+                    id: nt.id,
+                    name: nt.name,
+                })
+            })
+            .collect()
+    }
 }
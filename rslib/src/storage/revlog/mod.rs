@@ -10,11 +10,13 @@ use rusqlite::types::ValueRef;
 use rusqlite::OptionalExtension;
 use rusqlite::Row;
 
+use super::ids_to_string;
 use super::SqliteStorage;
 use crate::error::Result;
 use crate::prelude::*;
 use crate::revlog::RevlogEntry;
 use crate::revlog::RevlogReviewKind;
+use crate::stats::ReviewCounts;
 
 pub(crate) struct StudiedToday {
     pub cards: u32,
@@ -154,6 +156,25 @@ impl SqliteStorage {
             .collect()
     }
 
+    /// Call `func` with each review of the searched cards and the id of the
+    /// card's note, oldest first.
+    pub(crate) fn for_each_revlog_entry_of_searched_cards(
+        &self,
+        mut func: impl FnMut(RevlogEntry, NoteId) -> Result<()>,
+    ) -> Result<()> {
+        let mut stmt = self.db.prepare_cached(concat!(
+            "SELECT r.id, r.cid, r.usn, r.ease, cast(r.ivl AS integer), ",
+            "cast(r.lastIvl AS integer), r.factor, r.time, r.type, c.nid ",
+            "FROM revlog r JOIN cards c ON c.id = r.cid ",
+            "WHERE r.cid IN (SELECT cid FROM search_cids) ORDER BY r.id"
+        ))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            func(row_to_revlog_entry(row)?, row.get(9)?)?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn get_all_revlog_entries_in_card_order(&self) -> Result<Vec<RevlogEntry>> {
         self.db
             .prepare_cached(concat!(include_str!("get.sql"), " order by cid, id"))?
@@ -168,6 +189,60 @@ impl SqliteStorage {
             .collect()
     }
 
+    /// Returns the number of passed and failed review-type entries in the
+    /// provided time range, for cards whose home deck is one of `dids`.
+    pub(crate) fn review_pass_and_fail_counts(
+        &self,
+        dids: &[DeckId],
+        start: TimestampSecs,
+        end: TimestampSecs,
+    ) -> Result<(u32, u32)> {
+        let mut sql = format!(
+            "select coalesce(sum(r.ease > 1), 0), coalesce(sum(r.ease = 1), 0)
+from revlog r join cards c on c.id = r.cid
+where r.type = {} and r.id >= ? and r.id < ?
+and (case when c.odid = 0 then c.did else c.odid end) in ",
+            RevlogReviewKind::Review as u8
+        );
+        ids_to_string(&mut sql, dids);
+        self.db
+            .query_row(&sql, [start.as_millis().0, end.as_millis().0], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(Into::into)
+    }
+
+    /// Returns the number of new, learning and review answers logged since
+    /// `start`, for cards whose home deck is one of `dids`.
+    pub(crate) fn review_counts_since(
+        &self,
+        dids: &[DeckId],
+        start: TimestampSecs,
+    ) -> Result<ReviewCounts> {
+        let mut sql = format!(
+            "select coalesce(sum(r.type = {learn} and r.lastIvl = 0), 0),
+coalesce(sum((r.type = {learn} and r.lastIvl != 0) or r.type = {relearn}), 0),
+coalesce(sum(r.type in ({review}, {filtered})), 0)
+from revlog r join cards c on c.id = r.cid
+where r.id >= ?
+and (case when c.odid = 0 then c.did else c.odid end) in ",
+            learn = RevlogReviewKind::Learning as u8,
+            relearn = RevlogReviewKind::Relearning as u8,
+            review = RevlogReviewKind::Review as u8,
+            filtered = RevlogReviewKind::Filtered as u8,
+        );
+        ids_to_string(&mut sql, dids);
+        self.db
+            .query_row(&sql, [start.as_millis().0], |row| {
+                Ok(ReviewCounts {
+                    new: row.get(0)?,
+                    learning: row.get(1)?,
+                    review: row.get(2)?,
+                })
+            })
+            .map_err(Into::into)
+    }
+
     pub(crate) fn studied_today(&self, day_cutoff: TimestampSecs) -> Result<StudiedToday> {
         let start = day_cutoff.adding_secs(-86_400).as_millis();
         self.db
@@ -7,6 +7,7 @@ use std::collections::HashSet;
 use std::hash::Hasher;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use fnv::FnvHasher;
 use fsrs::FSRS;
@@ -24,6 +25,7 @@ use super::SchemaVersion;
 use crate::config::schema11::schema11_config_as_string;
 use crate::error::DbErrorKind;
 use crate::prelude::*;
+use crate::progress::ProgressState;
 use crate::scheduler::timing::local_minutes_west_for_stamp;
 use crate::scheduler::timing::v1_creation_date;
 use crate::storage::card::data::CardData;
@@ -518,6 +520,27 @@ impl SqliteStorage {
         Ok(())
     }
 
+    /// Run `func`, interrupting any SQL it executes with
+    /// [AnkiError::Interrupted] if another thread sets the `want_abort` flag
+    /// on `progress`. Like [crate::progress::ThrottlingProgressHandler], the
+    /// flag is cleared first, so a stale abort request does not interrupt
+    /// `func`.
+    pub(crate) fn with_abort_handler<T>(
+        &self,
+        progress: &Arc<Mutex<ProgressState>>,
+        func: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        progress.lock().unwrap().want_abort = false;
+        let progress = progress.clone();
+        self.db.progress_handler(
+            1000,
+            Some(move || std::mem::take(&mut progress.lock().unwrap().want_abort)),
+        );
+        let result = func();
+        self.db.progress_handler(1000, None::<fn() -> bool>);
+        result
+    }
+
     // Savepoints
     //////////////////////////////////////////
     //
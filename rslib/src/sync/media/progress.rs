@@ -5,6 +5,10 @@
 pub struct MediaSyncProgress {
     pub checked: usize,
     pub downloaded_files: usize,
+    /// Files the server has told us about so far that need downloading.
+    pub files_to_download: usize,
+    /// Size of the downloaded zip data.
+    pub downloaded_bytes: usize,
     pub downloaded_deletions: usize,
     pub uploaded_files: usize,
     pub uploaded_deletions: usize,
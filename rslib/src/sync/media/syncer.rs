@@ -35,6 +35,7 @@ pub struct MediaSyncer {
     mgr: MediaManager,
     client: HttpSyncClient,
     progress: ThrottlingProgressHandler<MediaSyncProgress>,
+    batch_callback: Box<dyn FnMut(MediaSyncProgress) + Send>,
 }
 
 impl MediaSyncer {
@@ -47,9 +48,21 @@ impl MediaSyncer {
             mgr,
             client,
             progress,
+            batch_callback: Box::new(|_| ()),
         })
     }
 
+    /// Call the provided function with the current progress after each batch
+    /// of files has been downloaded. Unlike the progress handler, this is not
+    /// throttled.
+    pub fn with_batch_callback(
+        mut self,
+        callback: impl FnMut(MediaSyncProgress) + Send + 'static,
+    ) -> Self {
+        self.batch_callback = Box::new(callback);
+        self
+    }
+
     pub async fn sync(&mut self, server_usn: Option<Usn>) -> Result<()> {
         self.sync_inner(server_usn).await.map_err(|e| {
             debug!("sync error: {:?}", e);
@@ -141,6 +154,8 @@ impl MediaSyncer {
 
             let (to_download, to_delete, to_remove_pending) =
                 changes::determine_required_changes(&self.mgr.db, batch)?;
+            self.progress
+                .update(false, |p| p.files_to_download += to_download.len())?;
 
             // file removal
             self.mgr.remove_files(to_delete.as_slice())?;
@@ -161,6 +176,7 @@ impl MediaSyncer {
                     .download_files(DownloadFilesRequest { files: batch }.try_into_sync_request()?)
                     .await?
                     .data;
+                let zip_len = zip_data.len();
                 let download_batch =
                     download::extract_into_media_folder(self.mgr.media_folder.as_path(), zip_data)?
                         .into_iter();
@@ -168,7 +184,11 @@ impl MediaSyncer {
                 dl_fnames = &dl_fnames[len..];
                 downloaded.extend(download_batch);
 
-                self.progress.update(false, |p| p.downloaded_files += len)?;
+                self.progress.state.downloaded_files += len;
+                self.progress.state.downloaded_bytes += zip_len;
+                (self.batch_callback)(self.progress.state);
+                // also checks whether the sync has been aborted
+                self.progress.check_cancelled()?;
             }
 
             // then update the DB
@@ -5,6 +5,8 @@
 
 use std::fs;
 use std::net::IpAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::thread::sleep;
 use std::time::Duration;
 
@@ -292,3 +294,31 @@ async fn parallel_requests() -> Result<()> {
     })
     .await
 }
+
+#[tokio::test]
+async fn batch_callback_reports_downloads() -> Result<()> {
+    with_active_server(|client| async move {
+        let ctx = SyncTestContext::new(client.clone());
+        let media1 = ctx.media1();
+        ctx.sleep();
+        media1.add_file("batch1", b"batch1").unwrap();
+        media1.add_file("batch2", b"batch2").unwrap();
+        ctx.sync_media1().await?;
+
+        let reported = Arc::new(Mutex::new(vec![]));
+        let reported2 = reported.clone();
+        MediaSyncer::new(ctx.media2(), ignore_progress(), ctx.client.clone())?
+            .with_batch_callback(move |progress| reported2.lock().unwrap().push(progress))
+            .sync(None)
+            .await?;
+
+        let reported = reported.lock().unwrap();
+        let last = reported.last().unwrap();
+        // may be higher when testing on external endpoint
+        assert!(last.downloaded_files >= 2);
+        assert_eq!(last.downloaded_files, last.files_to_download);
+        assert!(last.downloaded_bytes > 0);
+        Ok(())
+    })
+    .await
+}
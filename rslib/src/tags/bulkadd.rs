@@ -16,6 +16,15 @@ impl Collection {
     pub fn add_tags_to_notes(&mut self, nids: &[NoteId], tags: &str) -> Result<OpOutput<usize>> {
         self.transact(Op::UpdateTag, |col| col.add_tags_to_notes_inner(nids, tags))
     }
+
+    /// Add tags to all notes matching the search. Returns the number of notes
+    /// that were missing at least one of the tags.
+    pub fn add_tags_to_matching(&mut self, search: &str, tags: &str) -> Result<OpOutput<usize>> {
+        self.transact(Op::UpdateTag, |col| {
+            let nids = col.search_notes_unordered(search)?;
+            col.add_tags_to_notes_inner(&nids, tags)
+        })
+    }
 }
 
 impl Collection {
@@ -74,6 +83,7 @@ fn add_missing_tags(note_tags: &str, desired: &[UniCase<String>]) -> Option<Stri
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn add_missing() {
@@ -88,4 +98,29 @@ mod test {
         assert_eq!(&add_to("XYZ deF aaa"), " aaa abc deF XYZ ");
         assert!(add_missing_tags("def xyz abc", &desired).is_none());
     }
+
+    #[test]
+    fn add_tags_to_matching() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.basic_rev_notetype();
+        let note1 = NoteAdder::new(&nt).fields(&["foo", "bar"]).add(&mut col);
+        let note2 = NoteAdder::new(&nt).fields(&["foo", ""]).add(&mut col);
+        let mut note3 = NoteAdder::new(&nt).fields(&["baz", ""]).note();
+        note3.tags = vec!["a".into(), "b".into()];
+        col.add_note(&mut note3, DeckId(1))?;
+
+        // a note with several matching cards only counts once
+        assert_eq!(col.add_tags_to_matching("foo", "a B")?.output, 2);
+        // case matches the existing tag
+        let tags =
+            |col: &mut Collection, nid: NoteId| col.storage.get_note(nid).unwrap().unwrap().tags;
+        assert_eq!(tags(&mut col, note1.id), ["a", "b"]);
+        assert_eq!(tags(&mut col, note2.id), ["a", "b"]);
+        // notes that already have all tags are skipped
+        assert_eq!(col.add_tags_to_matching("", "a b")?.output, 0);
+        assert_eq!(col.add_tags_to_matching("", "c")?.output, 3);
+        assert!(col.storage.get_tag("c")?.is_some());
+
+        Ok(())
+    }
 }
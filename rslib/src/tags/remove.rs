@@ -4,6 +4,7 @@
 use unicase::UniCase;
 
 use super::matcher::TagMatcher;
+use super::split_tags;
 use crate::prelude::*;
 
 impl Collection {
@@ -13,6 +14,18 @@ impl Collection {
         self.transact(Op::RemoveTag, |col| col.remove_tags_inner(tags))
     }
 
+    /// Remove a single tag and all of its child tags from all notes and the
+    /// tag list. Tags that merely start with the same text, such as
+    /// `language` for `lang`, are left alone.
+    pub fn remove_tag_subtree(&mut self, prefix: &str) -> Result<OpOutput<usize>> {
+        let prefix = prefix.trim().trim_end_matches("::");
+        require!(
+            !prefix.is_empty() && split_tags(prefix).count() == 1,
+            "expected a single tag"
+        );
+        self.transact(Op::RemoveTag, |col| col.remove_tags_inner(prefix))
+    }
+
     /// Remove whitespace-separated tags from provided notes.
     pub fn remove_tags_from_notes(
         &mut self,
@@ -125,4 +138,36 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn removing_subtree() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        let mut add_note = |tags: &[&str]| -> Result<NoteId> {
+            let mut note = nt.new_note();
+            note.tags = tags.iter().map(ToString::to_string).collect();
+            col.add_note(&mut note, DeckId(1))?;
+            Ok(note.id)
+        };
+        let nid1 = add_note(&["lang", "lang::de::verbs", "other"])?;
+        let nid2 = add_note(&["language", "Lang::fr"])?;
+        let nid3 = add_note(&["lang_x", "language"])?;
+
+        assert_eq!(col.remove_tag_subtree("lang::")?.output, 2);
+        let tags = |col: &Collection, nid: NoteId| col.storage.get_note(nid).unwrap().unwrap().tags;
+        assert_eq!(tags(&col, nid1), ["other"]);
+        assert_eq!(tags(&col, nid2), ["language"]);
+        assert_eq!(tags(&col, nid3), ["lang_x", "language"]);
+        let mut registered: Vec<_> = col
+            .storage
+            .all_tags()?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        registered.sort_unstable();
+        assert_eq!(registered, ["lang_x", "language", "other"]);
+        assert!(col.remove_tag_subtree("a b").is_err());
+
+        Ok(())
+    }
 }
@@ -70,3 +70,68 @@ impl Collection {
         Ok(match_count)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::tests::NoteAdder;
+
+    fn tag_set<'a>(tags: impl IntoIterator<Item = &'a String>) -> HashSet<&'a str> {
+        tags.into_iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn rename_renames_children_but_not_substrings() -> Result<()> {
+        let mut col = Collection::new();
+        let mut note = NoteAdder::basic(&mut col).note();
+        note.tags = ["lang", "lang::es", "lang::es::verbs", "lang::esperanto"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        col.add_note(&mut note, DeckId(1))?;
+        let mut other = NoteAdder::basic(&mut col).note();
+        other.tags = vec!["language".into()];
+        col.add_note(&mut other, DeckId(1))?;
+
+        assert_eq!(col.rename_tag("lang::es", "lang::spanish")?.output, 1);
+        let renamed = col.storage.get_note(note.id)?.unwrap();
+        assert_eq!(
+            tag_set(&renamed.tags),
+            HashSet::from([
+                "lang",
+                "lang::spanish",
+                "lang::spanish::verbs",
+                "lang::esperanto"
+            ])
+        );
+        assert_eq!(renamed.usn, col.usn()?);
+        // unrelated notes are left alone
+        assert_eq!(col.storage.get_note(other.id)?.unwrap(), other);
+        // and the tag list is updated
+        let registered: Vec<_> = col
+            .storage
+            .all_tags()?
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(
+            tag_set(&registered),
+            HashSet::from([
+                "lang",
+                "lang::spanish",
+                "lang::spanish::verbs",
+                "lang::esperanto",
+                "language"
+            ])
+        );
+
+        // a single undo restores the old names
+        col.undo()?;
+        assert_eq!(col.storage.get_note(note.id)?.unwrap(), note);
+        assert!(col.storage.get_tag("lang::es::verbs")?.is_some());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright: Ankitects Pty Ltd and contributors
 // License: GNU AGPL, version 3 or later; http://www.gnu.org/licenses/agpl.html
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::iter::Peekable;
 
@@ -12,9 +13,15 @@ use super::Tag;
 use crate::prelude::*;
 
 impl Collection {
-    pub fn tag_tree(&mut self) -> Result<TagTreeNode> {
+    /// All registered tags arranged by their `::` hierarchy. Each node
+    /// includes the number of notes tagged with it directly, and the number
+    /// tagged with it or any of its children.
+    pub fn tag_tree(&self) -> Result<TagTreeNode> {
         let tags = self.storage.all_tags()?;
-        let tree = tags_to_tree(tags);
+        let (tagged_notes, counts) = self.storage.note_counts_by_tag()?;
+        let mut tree = tags_to_tree(tags);
+        tree.total_note_count = tagged_notes;
+        add_note_counts(&mut tree, "", &counts);
 
         Ok(tree)
     }
@@ -85,6 +92,7 @@ fn add_child_nodes(tags: &mut Peekable<impl Iterator<Item = Tag>>, parent: &mut
                     children: vec![],
                     level: parent.level + 1,
                     collapsed: !tag.expanded,
+                    ..Default::default()
                 });
                 tags.next();
             }
@@ -101,6 +109,25 @@ fn add_child_nodes(tags: &mut Peekable<impl Iterator<Item = Tag>>, parent: &mut
     }
 }
 
+fn add_note_counts(
+    parent: &mut TagTreeNode,
+    parent_name: &str,
+    counts: &HashMap<UniCase<String>, (u32, u32)>,
+) {
+    for child in &mut parent.children {
+        let name = if parent_name.is_empty() {
+            child.name.clone()
+        } else {
+            format!("{parent_name}\x1f{}", child.name)
+        };
+        if let Some(&(direct, total)) = counts.get(&UniCase::new(name.clone())) {
+            child.note_count = direct;
+            child.total_note_count = total;
+        }
+        add_note_counts(child, &name, counts);
+    }
+}
+
 /// For the given tag, check if immediate parent exists. If so, add
 /// tag and return.
 /// If the immediate parent is missing, check and add any missing parents.
@@ -125,10 +152,14 @@ fn add_tag_and_missing_parents<'a, 'b>(
 mod test {
     use super::*;
 
+    /// The tests below use a single note, so every node has a total count of
+    /// 1, and only leaves are tagged directly unless [tagged] is used.
     fn node(name: &str, level: u32, children: Vec<TagTreeNode>) -> TagTreeNode {
         TagTreeNode {
             name: name.into(),
             level,
+            note_count: children.is_empty() as u32,
+            total_note_count: 1,
             children,
             collapsed: level != 0,
         }
@@ -138,6 +169,13 @@ mod test {
         node(name, level, vec![])
     }
 
+    fn tagged(node: TagTreeNode) -> TagTreeNode {
+        TagTreeNode {
+            note_count: 1,
+            ..node
+        }
+    }
+
     #[test]
     fn tree() -> Result<()> {
         let mut col = Collection::new();
@@ -193,7 +231,11 @@ mod test {
                 vec![node(
                     "foo",
                     1,
-                    vec![node("bar", 2, vec![node("baz", 3, vec![leaf("quux", 4)])])]
+                    vec![node(
+                        "bar",
+                        2,
+                        vec![tagged(node("baz", 3, vec![leaf("quux", 4)]))]
+                    )]
                 )]
             )
         );
@@ -210,7 +252,10 @@ mod test {
             node(
                 "",
                 0,
-                vec![node("one", 1, vec![leaf("two", 2)]), leaf("one1", 1)]
+                vec![
+                    tagged(node("one", 1, vec![leaf("two", 2)])),
+                    leaf("one1", 1)
+                ]
             )
         );
 
@@ -224,4 +269,32 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn note_counts() -> Result<()> {
+        let mut col = Collection::new();
+        let nt = col.get_notetype_by_name("Basic")?.unwrap();
+        for tags in [
+            &["lang::de", "lang::de::verbs"][..],
+            &["lang"],
+            &["lang::fr"],
+            &[],
+        ] {
+            let mut note = nt.new_note();
+            note.tags = tags.iter().map(ToString::to_string).collect();
+            col.add_note(&mut note, DeckId(1))?;
+        }
+
+        let counts = |node: &TagTreeNode| (node.note_count, node.total_note_count);
+        let tree = col.tag_tree()?;
+        assert_eq!(counts(&tree), (0, 3));
+        let lang = &tree.children[0];
+        assert_eq!(counts(lang), (1, 3));
+        let de = &lang.children[0];
+        assert_eq!(counts(de), (1, 1));
+        assert_eq!(counts(&de.children[0]), (1, 1));
+        assert_eq!(counts(&lang.children[1]), (1, 1));
+
+        Ok(())
+    }
 }
@@ -66,29 +66,35 @@ fn next_token(input: &str) -> nom::IResult<&str, Token> {
     alt((handlebar_token, text_token))(input)
 }
 
-fn tokens<'a>(template: &'a str) -> Box<dyn Iterator<Item = TemplateResult<Token>> + 'a> {
+/// Tokens are returned with their byte offset into the template.
+fn tokens<'a>(template: &'a str) -> Box<dyn Iterator<Item = TemplateResult<(usize, Token)>> + 'a> {
     if template.trim_start().starts_with(ALT_HANDLEBAR_DIRECTIVE) {
-        Box::new(legacy_tokens(
-            template
-                .trim_start()
-                .trim_start_matches(ALT_HANDLEBAR_DIRECTIVE),
-        ))
+        let data = template
+            .trim_start()
+            .trim_start_matches(ALT_HANDLEBAR_DIRECTIVE);
+        Box::new(legacy_tokens(data, template.len() - data.len()))
     } else {
         Box::new(new_tokens(template))
     }
 }
 
-fn new_tokens(mut data: &str) -> impl Iterator<Item = TemplateResult<Token>> {
+fn new_tokens(mut data: &str) -> impl Iterator<Item = TemplateResult<(usize, Token)>> {
+    let mut offset = 0;
     iter::from_fn(move || {
         if data.is_empty() {
             return None;
         }
         match next_token(data) {
             Ok((i, o)) => {
+                let start = offset;
+                offset += data.len() - i.len();
                 data = i;
-                Some(Ok(o))
+                Some(Ok((start, o)))
             }
-            Err(_e) => Some(Err(TemplateError::NoClosingBrackets(data.to_string()))),
+            Err(_e) => Some(Err(TemplateError::NoClosingBrackets {
+                tag: data.to_string(),
+                offset,
+            })),
         }
     })
 }
@@ -158,17 +164,25 @@ fn alternate_handlebar_token(s: &str) -> nom::IResult<&str, Token> {
     })(s)
 }
 
-fn legacy_tokens(mut data: &str) -> impl Iterator<Item = TemplateResult<Token>> {
+fn legacy_tokens(
+    mut data: &str,
+    mut offset: usize,
+) -> impl Iterator<Item = TemplateResult<(usize, Token)>> {
     iter::from_fn(move || {
         if data.is_empty() {
             return None;
         }
         match legacy_next_token(data) {
             Ok((i, o)) => {
+                let start = offset;
+                offset += data.len() - i.len();
                 data = i;
-                Some(Ok(o))
+                Some(Ok((start, o)))
             }
-            Err(_e) => Some(Err(TemplateError::NoClosingBrackets(data.to_string()))),
+            Err(_e) => Some(Err(TemplateError::NoClosingBrackets {
+                tag: data.to_string(),
+                offset,
+            })),
         }
     })
 }
@@ -204,15 +218,17 @@ impl ParsedTemplate {
     }
 }
 
-fn parse_inner<'a, I: Iterator<Item = TemplateResult<Token<'a>>>>(
+/// `open_tag` is the currently open conditional and its offset.
+fn parse_inner<'a, I: Iterator<Item = TemplateResult<(usize, Token<'a>)>>>(
     iter: &mut I,
-    open_tag: Option<&'a str>,
+    open_tag: Option<(usize, &'a str)>,
 ) -> TemplateResult<Vec<ParsedNode>> {
     let mut nodes = vec![];
 
     while let Some(token) = iter.next() {
         use Token::*;
-        nodes.push(match token? {
+        let (offset, token) = token?;
+        nodes.push(match token {
             Text(t) => ParsedNode::Text(t.into()),
             Replacement(t) => {
                 let mut it = t.rsplit(':');
@@ -223,14 +239,14 @@ fn parse_inner<'a, I: Iterator<Item = TemplateResult<Token<'a>>>>(
             }
             OpenConditional(t) => ParsedNode::Conditional {
                 key: t.into(),
-                children: parse_inner(iter, Some(t))?,
+                children: parse_inner(iter, Some((offset, t)))?,
             },
             OpenNegated(t) => ParsedNode::NegatedConditional {
                 key: t.into(),
-                children: parse_inner(iter, Some(t))?,
+                children: parse_inner(iter, Some((offset, t)))?,
             },
             CloseConditional(t) => {
-                let currently_open = if let Some(open) = open_tag {
+                let currently_open = if let Some((_, open)) = open_tag {
                     if open == t {
                         // matching closing tag, move back to parent
                         return Ok(nodes);
@@ -243,13 +259,17 @@ fn parse_inner<'a, I: Iterator<Item = TemplateResult<Token<'a>>>>(
                 return Err(TemplateError::ConditionalNotOpen {
                     closed: t.to_string(),
                     currently_open,
+                    offset,
                 });
             }
         });
     }
 
-    if let Some(open) = open_tag {
-        Err(TemplateError::ConditionalNotClosed(open.to_string()))
+    if let Some((offset, open)) = open_tag {
+        Err(TemplateError::ConditionalNotClosed {
+            tag: open.to_string(),
+            offset,
+        })
     } else {
         Ok(nodes)
     }
@@ -277,17 +297,18 @@ fn template_error_to_anki_error(
     AnkiError::TemplateError { info: source }
 }
 
-fn localized_template_error(tr: &I18n, err: TemplateError) -> String {
+pub(crate) fn localized_template_error(tr: &I18n, err: TemplateError) -> String {
     match err {
-        TemplateError::NoClosingBrackets(tag) => tr
+        TemplateError::NoClosingBrackets { tag, .. } => tr
             .card_template_rendering_no_closing_brackets("}}", tag)
             .into(),
-        TemplateError::ConditionalNotClosed(tag) => tr
+        TemplateError::ConditionalNotClosed { tag, .. } => tr
             .card_template_rendering_conditional_not_closed(format!("{{{{/{}}}}}", tag))
             .into(),
         TemplateError::ConditionalNotOpen {
             closed,
             currently_open,
+            ..
         } => if let Some(open) = currently_open {
             tr.card_template_rendering_wrong_conditional_closed(
                 format!("{{{{/{}}}}}", closed),
@@ -966,6 +987,38 @@ mod test {
         PT::from_text("{{/matched}}").unwrap_err();
         PT::from_text("{{#mis}}").unwrap_err();
 
+        // errors point at the offending tag
+        assert_eq!(
+            PT::from_text("foo {{#mis}} {{/matched}}").unwrap_err(),
+            TemplateError::ConditionalNotOpen {
+                closed: "matched".into(),
+                currently_open: Some("mis".into()),
+                offset: 13,
+            }
+        );
+        assert_eq!(
+            PT::from_text("{{a}} {{^mis}}").unwrap_err(),
+            TemplateError::ConditionalNotClosed {
+                tag: "mis".into(),
+                offset: 6,
+            }
+        );
+        assert_eq!(
+            PT::from_text(" {{=<% %>=}}<%a%> <%/b%>").unwrap_err(),
+            TemplateError::ConditionalNotOpen {
+                closed: "b".into(),
+                currently_open: None,
+                offset: 18,
+            }
+        );
+        assert_eq!(
+            PT::from_text("a {{b").unwrap_err(),
+            TemplateError::NoClosingBrackets {
+                tag: "{{b".into(),
+                offset: 2,
+            }
+        );
+
         // whitespace
         assert_eq!(
             PT::from_text("{{ tag }}").unwrap().0,
@@ -12,6 +12,7 @@ use crate::collection::CollectionBuilder;
 use crate::deckconfig::DeckConfigInner;
 use crate::media::MediaManager;
 use crate::prelude::*;
+use crate::revlog::RevlogEntry;
 
 pub(crate) fn open_fs_test_collection(name: &str) -> (Collection, TempDir) {
     let tempdir = tempdir().unwrap();
@@ -93,6 +94,14 @@ impl Collection {
             .unwrap();
     }
 
+    /// Adds a basic note to the provided deck, and logs the entry for its
+    /// card.
+    pub(crate) fn add_revlog_entry_for_new_note(&mut self, deck: DeckId, mut entry: RevlogEntry) {
+        let note = NoteAdder::basic(self).deck(deck).add(self);
+        entry.cid = self.storage.card_ids_of_notes(&[note.id]).unwrap()[0];
+        self.storage.add_revlog_entry(&entry, true).unwrap();
+    }
+
     pub(crate) fn basic_notetype(&self) -> Notetype {
         let ntid = self.storage.get_notetype_id("Basic").unwrap().unwrap();
         self.storage.get_notetype(ntid).unwrap().unwrap()
@@ -42,10 +42,14 @@ impl Default for UndoMode {
     }
 }
 
+/// The ops that would be undone/redone next, which can be shown to the user
+/// with [Op::describe], and the number of steps on each stack.
 pub struct UndoStatus {
     pub undo: Option<Op>,
     pub redo: Option<Op>,
     pub last_step: usize,
+    pub undo_steps: usize,
+    pub redo_steps: usize,
 }
 
 pub struct UndoOutput {
@@ -65,6 +69,17 @@ pub(crate) struct UndoManager {
     mode: UndoMode,
     current_step: Option<UndoableOp>,
     counter: usize,
+    group: Option<UndoGroup>,
+}
+
+/// An open group of ops that are merged into a single undo step as they
+/// complete.
+#[derive(Debug)]
+struct UndoGroup {
+    /// The counter of the step changes are merged into.
+    counter: usize,
+    /// Nested groups are merged into the outermost one.
+    depth: usize,
 }
 
 impl UndoManager {
@@ -98,6 +113,8 @@ impl UndoManager {
             if step.has_changes() && !skip_undo {
                 if self.mode == UndoMode::Undoing {
                     self.redo_steps.push(step);
+                } else if let Some(target) = self.group_target() {
+                    target.changes.extend(step.changes);
                 } else {
                     self.undo_steps.truncate(UNDO_LIMIT - 1);
                     self.undo_steps.push_front(step);
@@ -164,6 +181,36 @@ impl UndoManager {
         })
     }
 
+    /// The step of the open group, if the group's step is still the most
+    /// recent one and a normal op is being performed.
+    fn group_target(&mut self) -> Option<&mut UndoableOp> {
+        let counter = self.group.as_ref()?.counter;
+        if self.mode != UndoMode::NormalOp {
+            return None;
+        }
+        self.undo_steps
+            .front_mut()
+            .filter(|step| step.counter == counter)
+    }
+
+    fn begin_group(&mut self, name: String) {
+        if let Some(group) = self.group.as_mut() {
+            group.depth += 1;
+        } else {
+            let counter = self.add_custom_step(name);
+            self.group = Some(UndoGroup { counter, depth: 1 });
+        }
+    }
+
+    fn end_group(&mut self) -> Result<()> {
+        let group = self.group.as_mut().or_invalid("no undo group open")?;
+        group.depth -= 1;
+        if group.depth == 0 {
+            self.group = None;
+        }
+        Ok(())
+    }
+
     /// Start a new step with a custom name, and return its associated
     /// counter value, which can be used with `merge_undoable_ops`.
     fn add_custom_step(&mut self, name: String) -> usize {
@@ -202,6 +249,8 @@ impl Collection {
             undo: self.can_undo().cloned(),
             redo: self.can_redo().cloned(),
             last_step: self.state.undo.counter,
+            undo_steps: self.state.undo.undo_steps.len(),
+            redo_steps: self.state.undo.redo_steps.len(),
         }
     }
 
@@ -216,6 +265,28 @@ impl Collection {
     pub fn add_custom_undo_step(&mut self, name: String) -> usize {
         self.state.undo.add_custom_step(name)
     }
+
+    /// Merge the undoable ops performed until the matching
+    /// [Collection::end_undo_group] into a single step named `name`. Groups
+    /// opened while one is already open are merged into the outer group.
+    pub fn begin_undo_group(&mut self, name: String) {
+        self.state.undo.begin_group(name)
+    }
+
+    pub fn end_undo_group(&mut self) -> Result<()> {
+        self.state.undo.end_group()
+    }
+
+    /// Run `func` inside an undo group; see [Collection::begin_undo_group].
+    pub fn with_undo_group<F, R>(&mut self, name: String, func: F) -> Result<R>
+    where
+        F: FnOnce(&mut Collection) -> Result<R>,
+    {
+        self.begin_undo_group(name);
+        let result = func(self);
+        self.end_undo_group()?;
+        result
+    }
 }
 
 impl Collection {
@@ -333,6 +404,8 @@ mod test {
     use super::UndoableChange;
     use crate::card::Card;
     use crate::prelude::*;
+    use crate::tests::DeckAdder;
+    use crate::tests::NoteAdder;
 
     #[test]
     fn undo() -> Result<()> {
@@ -442,6 +515,72 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn undo_status() -> Result<()> {
+        let mut col = Collection::new();
+        let status = col.undo_status();
+        assert_eq!((status.undo, status.redo), (None, None));
+        assert_eq!((status.undo_steps, status.redo_steps), (0, 0));
+
+        DeckAdder::new("one").add(&mut col);
+        NoteAdder::basic(&mut col).add(&mut col);
+        let status = col.undo_status();
+        assert_eq!(status.undo, Some(Op::AddNote));
+        assert_eq!(status.redo, None);
+        assert_eq!((status.undo_steps, status.redo_steps), (2, 0));
+        assert_eq!(status.undo.unwrap().describe(&col.tr), "Add Note");
+
+        col.undo()?;
+        let status = col.undo_status();
+        assert_eq!(status.undo, Some(Op::AddDeck));
+        assert_eq!(status.redo, Some(Op::AddNote));
+        assert_eq!((status.undo_steps, status.redo_steps), (1, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_group() -> Result<()> {
+        let mut col = Collection::new();
+        let set_due = |col: &mut Collection, cid: CardId, due: i32| {
+            col.transact(Op::UpdateCard, |col| {
+                col.get_and_update_card(cid, |card| {
+                    card.due = due;
+                    Ok(())
+                })
+            })
+        };
+        let note = NoteAdder::basic(&mut col).add(&mut col);
+        let cid = col.storage.all_cards_of_note(note.id)?[0].id;
+        set_due(&mut col, cid, 10)?;
+
+        col.with_undo_group("burst".into(), |col| {
+            set_due(col, cid, 20)?;
+            col.with_undo_group("inner".into(), |col| set_due(col, cid, 30))?;
+            DeckAdder::new("deck").add(col);
+            Ok(())
+        })?;
+        let status = col.undo_status();
+        assert_eq!(status.undo, Some(Op::Custom("burst".into())));
+        assert_eq!(status.undo_steps, 3);
+        assert!(col.get_deck_id("deck")?.is_some());
+
+        // a single undo reverts the whole group
+        col.undo()?;
+        assert_eq!(col.storage.get_card(cid)?.unwrap().due, 10);
+        assert!(col.get_deck_id("deck")?.is_none());
+        assert_eq!(col.undo_status().undo, Some(Op::UpdateCard));
+        col.redo()?;
+        assert_eq!(col.storage.get_card(cid)?.unwrap().due, 30);
+
+        // once the group is closed, ops get their own steps again
+        set_due(&mut col, cid, 40)?;
+        assert_eq!(col.undo_status().undo, Some(Op::UpdateCard));
+        assert!(col.end_undo_group().is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn custom() -> Result<()> {
         let mut col = Collection::new();